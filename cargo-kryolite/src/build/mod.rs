@@ -0,0 +1,115 @@
+use std::{env, fs, io::ErrorKind, path::{Path, PathBuf}, process::Command};
+
+use kryolite_manifest::Contract;
+use serde_json::Value;
+
+use crate::wasm;
+
+const TARGET: &str = "wasm32-unknown-unknown";
+
+/// Built contract, ready in `pkg/`.
+pub struct Artifact {
+  pub name: String,
+  pub wasm: PathBuf,
+  pub exports: Vec<String>,
+  /// Manifest embedded in the code.
+  pub manifest: Contract
+}
+
+/// Builds the crate in the current directory for wasm, then checks, strips and optimizes
+/// the result into `pkg/<name>.wasm` and writes its embedded manifest to `pkg/manifest.json`.
+/// `cargo_args` are passed on to `cargo build`.
+pub fn build(cargo_args: &[String]) -> Result<Artifact, String> {
+  let status = Command::new(cargo())
+    .args(["build", "--target", TARGET, "--release"])
+    .args(cargo_args)
+    .status()
+    .map_err(|err| format!("failed to run cargo: {}", err))?;
+
+  if !status.success() {
+    return Err("cargo build failed".into());
+  }
+
+  let (name, built) = locate()?;
+  let code = fs::read(&built).map_err(|err| format!("failed to read {}: {}", built.display(), err))?;
+
+  let exports = wasm::check(&code).map_err(|problems| {
+    let problems: Vec<String> = problems.iter().map(|problem| format!("  {}", problem)).collect();
+    format!("{} can't be deployed:\n{}", built.display(), problems.join("\n"))
+  })?;
+
+  let stripped = wasm::strip(&code);
+  let manifest = Contract::from_wasm(&stripped).map_err(|err| format!("{}: {}", built.display(), err))?;
+
+  let _ = fs::create_dir("pkg");
+  let out = PathBuf::from("pkg").join(format!("{}.wasm", name));
+
+  fs::write(&out, &stripped).map_err(|err| format!("failed to write {}: {}", out.display(), err))?;
+  optimize(&out)?;
+
+  // the code is the source of truth, the file is a copy for tools that don't read wasm
+  let manifest_out = PathBuf::from("pkg").join("manifest.json");
+  fs::write(&manifest_out, manifest.to_json()).map_err(|err| format!("failed to write {}: {}", manifest_out.display(), err))?;
+
+  let size = fs::metadata(&out).map(|meta| meta.len()).unwrap_or(0);
+  println!("{}: {} bytes (built {} bytes)", out.display(), size, code.len());
+
+  Ok(Artifact { name, wasm: out, exports, manifest })
+}
+
+fn cargo() -> String {
+  env::var("CARGO").unwrap_or_else(|_| "cargo".into())
+}
+
+// finds the cdylib of the package in the current directory
+fn locate() -> Result<(String, PathBuf), String> {
+  let output = Command::new(cargo())
+    .args(["metadata", "--no-deps", "--format-version", "1"])
+    .output()
+    .map_err(|err| format!("failed to run cargo metadata: {}", err))?;
+
+  if !output.status.success() {
+    return Err(format!("cargo metadata failed: {}", String::from_utf8_lossy(&output.stderr)));
+  }
+
+  let metadata: Value = serde_json::from_slice(&output.stdout).map_err(|err| format!("failed to read cargo metadata: {}", err))?;
+  let manifest_path = env::current_dir().map(|dir| dir.join("Cargo.toml")).unwrap_or_default();
+
+  let packages = metadata["packages"].as_array().cloned().unwrap_or_default();
+  let package = match packages.iter().find(|package| package["manifest_path"].as_str().map(Path::new) == Some(&manifest_path)) {
+    Some(package) => package,
+    None if packages.len() == 1 => &packages[0],
+    None => return Err("run cargo kryolite in the directory of the contract crate".into())
+  };
+
+  let targets = package["targets"].as_array().cloned().unwrap_or_default();
+  let lib = targets.iter()
+    .find(|target| target["crate_types"].as_array().is_some_and(|types| types.iter().any(|ty| ty == "cdylib")))
+    .ok_or("the contract has to be a cdylib, add `crate-type = [\"cdylib\"]` to [lib] in Cargo.toml")?;
+
+  let name = lib["name"].as_str().unwrap_or_default().replace('-', "_");
+  let target_dir = metadata["target_directory"].as_str().unwrap_or("target");
+  let wasm = Path::new(target_dir).join(TARGET).join("release").join(format!("{}.wasm", name));
+
+  Ok((name, wasm))
+}
+
+// wasm-opt is optional, rustc already optimizes for size with the release profile
+fn optimize(wasm: &Path) -> Result<(), String> {
+  let status = Command::new("wasm-opt")
+    .args(["-Oz", "--strip-debug", "--strip-producers"])
+    .arg(wasm)
+    .arg("-o")
+    .arg(wasm)
+    .status();
+
+  match status {
+    Ok(status) if status.success() => Ok(()),
+    Ok(_) => Err(format!("wasm-opt failed on {}", wasm.display())),
+    Err(err) if err.kind() == ErrorKind::NotFound => {
+      eprintln!("wasm-opt not found, skipping optimization (it comes with binaryen)");
+      Ok(())
+    }
+    Err(err) => Err(format!("failed to run wasm-opt: {}", err))
+  }
+}
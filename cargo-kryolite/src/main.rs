@@ -0,0 +1,167 @@
+mod build;
+mod package;
+mod wasm;
+
+use std::{fs, process::exit};
+
+use data_encoding::HEXLOWER;
+use kryolite_abi::typescript;
+use kryolite_manifest::{validate, Contract, Method};
+
+use build::build;
+use package::{package_hash, Package};
+
+const MANIFEST: &str = "pkg/manifest.json";
+
+const USAGE: &str = "usage: cargo kryolite <command>
+
+commands:
+  build [cargo args]          build the contract into pkg/<name>.wasm and its manifest into
+                              pkg/manifest.json
+  manifest [file]             print and validate the manifest of a manifest.json or .wasm file,
+                              pkg/manifest.json by default
+  package [cargo args]        build and bundle code and manifest into pkg/<name>.kryo
+  bindings [file] [out]       write TypeScript bindings for the manifest of a manifest.json or .wasm
+                              file, to pkg/<contract>.ts by default";
+
+fn main() {
+  let mut args: Vec<String> = std::env::args().skip(1).collect();
+
+  // cargo passes the subcommand name along when run as `cargo kryolite`
+  if args.first().map(|arg| arg.as_str()) == Some("kryolite") {
+    args.remove(0);
+  }
+
+  let result = match args.first().map(|arg| arg.as_str()) {
+    Some("build") => build(&args[1..]).map(|_| ()),
+    Some("manifest") if args.len() <= 2 => manifest(args.get(1).map(|arg| arg.as_str()).unwrap_or(MANIFEST)),
+    Some("package") => package(&args[1..]),
+    Some("bindings") if args.len() <= 3 => bindings(args.get(1).map(|arg| arg.as_str()).unwrap_or(MANIFEST), args.get(2).map(|arg| arg.as_str())),
+    _ => {
+      eprintln!("{}", USAGE);
+      exit(2);
+    }
+  };
+
+  if let Err(err) = result {
+    eprintln!("error: {}", err);
+    exit(1);
+  }
+}
+
+fn manifest(path: &str) -> Result<(), String> {
+  let contract = load(path)?;
+
+  print!("{}", describe(&contract));
+  check_manifest(&contract)?;
+
+  println!("{} is valid", path);
+  Ok(())
+}
+
+fn package(cargo_args: &[String]) -> Result<(), String> {
+  let artifact = build(cargo_args)?;
+  let contract = artifact.manifest;
+
+  check_manifest(&contract)?;
+
+  // every method in the manifest is exported under its own name
+  let missing: Vec<&str> = contract.methods.iter()
+    .map(|method| method.name.as_str())
+    .filter(|name| !artifact.exports.iter().any(|export| export == name))
+    .collect();
+
+  if !missing.is_empty() {
+    return Err(format!("the embedded manifest lists methods the code doesn't export: {}", missing.join(", ")));
+  }
+
+  let code = fs::read(&artifact.wasm).map_err(|err| format!("failed to read {}: {}", artifact.wasm.display(), err))?;
+  let bytes = Package { manifest: contract, code }.encode();
+  let out = format!("pkg/{}.kryo", artifact.name);
+
+  fs::write(&out, &bytes).map_err(|err| format!("failed to write {}: {}", out, err))?;
+
+  // read it back the way the node will
+  Package::decode(&bytes)?;
+
+  println!("{}: {} bytes, sha256 {}", out, bytes.len(), HEXLOWER.encode(package_hash(&bytes)));
+  Ok(())
+}
+
+fn bindings(path: &str, out: Option<&str>) -> Result<(), String> {
+  let contract = load(path)?;
+  check_manifest(&contract)?;
+
+  let ts = typescript(&contract).map_err(|err| err.to_string())?;
+  let out = out.map(String::from).unwrap_or_else(|| format!("pkg/{}.ts", contract.name));
+
+  fs::write(&out, ts).map_err(|err| format!("failed to write {}: {}", out, err))?;
+
+  println!("{}: bindings for {} methods", out, contract.methods.len());
+  Ok(())
+}
+
+fn load(path: &str) -> Result<Contract, String> {
+  let bytes = fs::read(path).map_err(|err| format!("failed to read {}: {}", path, err))?;
+
+  match path.ends_with(".wasm") {
+    true => Contract::from_wasm(&bytes).map_err(|err| format!("{}: {}", path, err)),
+    false => Contract::from_json(&String::from_utf8_lossy(&bytes)).map_err(|err| format!("failed to parse {}: {}", path, err))
+  }
+}
+
+fn check_manifest(contract: &Contract) -> Result<(), String> {
+  let problems: Vec<String> = validate(contract).iter().map(|problem| format!("  {}", problem)).collect();
+
+  match problems.is_empty() {
+    true => Ok(()),
+    false => Err(format!("invalid manifest:\n{}", problems.join("\n")))
+  }
+}
+
+fn describe(contract: &Contract) -> String {
+  let mut out = format!("{} (state version {}, {} abi)\n", contract.name, contract.version, contract.abi.as_str());
+
+  if !contract.interfaces.is_empty() {
+    out += &format!("implements {}\n", contract.interfaces.join(", "));
+  }
+
+  out += "methods:\n";
+
+  let mut seen: Vec<&str> = Vec::new();
+
+  for method in &contract.methods {
+    if !seen.contains(&method.name.as_str()) {
+      seen.push(&method.name);
+      out += &format!("  {}\n", signature(method));
+    }
+  }
+
+  if !contract.events.is_empty() {
+    out += "events:\n";
+
+    for event in &contract.events {
+      out += &format!("  {}({} params)\n", event.name, event.param_count);
+    }
+  }
+
+  out
+}
+
+fn signature(method: &Method) -> String {
+  let params: Vec<String> = method.method_params.iter()
+    .map(|param| format!("{}: {}", param.name, param.param_type))
+    .collect();
+
+  let mut signature = format!("{}({})", method.name, params.join(", "));
+
+  if method.return_value.value_type != "void" {
+    signature += &format!(" -> {}", method.return_value.value_type);
+  }
+
+  if method.readonly {
+    signature += " [readonly]";
+  }
+
+  signature
+}
@@ -0,0 +1,148 @@
+//! Deployable artifact bundling the contract code with its manifest.
+//!
+//! Layout, integers little endian:
+//!
+//! ```text
+//! "KRYO" | format: u8 | manifest length: u32 | manifest JSON | code length: u32 | code | sha256: [u8; 32]
+//! ```
+//!
+//! The hash covers everything before it, so a package can be checked without trusting where it came from.
+
+use kryolite_manifest::Contract;
+use sha2::{Digest, Sha256};
+
+const MAGIC: &[u8; 4] = b"KRYO";
+const FORMAT: u8 = 1;
+const HASH_LEN: usize = 32;
+
+pub struct Package {
+  pub manifest: Contract,
+  pub code: Vec<u8>
+}
+
+impl Package {
+  pub fn encode(&self) -> Vec<u8> {
+    let manifest = serde_json::to_vec(&self.manifest).unwrap();
+
+    let mut bytes = MAGIC.to_vec();
+    bytes.push(FORMAT);
+    bytes.extend_from_slice(&(manifest.len() as u32).to_le_bytes());
+    bytes.extend_from_slice(&manifest);
+    bytes.extend_from_slice(&(self.code.len() as u32).to_le_bytes());
+    bytes.extend_from_slice(&self.code);
+
+    let hash = Sha256::digest(&bytes);
+    bytes.extend_from_slice(&hash);
+    bytes
+  }
+
+  pub fn decode(bytes: &[u8]) -> Result<Package, String> {
+    if bytes.len() < MAGIC.len() + 1 + HASH_LEN || &bytes[..MAGIC.len()] != MAGIC {
+      return Err("not a kryolite package".into());
+    }
+
+    let (content, hash) = bytes.split_at(bytes.len() - HASH_LEN);
+
+    if Sha256::digest(content).as_slice() != hash {
+      return Err("hash doesn't match the contents, the package is corrupted".into());
+    }
+
+    if content[MAGIC.len()] != FORMAT {
+      return Err(format!("package format {} is not supported", content[MAGIC.len()]));
+    }
+
+    let mut rest = &content[MAGIC.len() + 1..];
+    let manifest = take(&mut rest)?;
+    let code = take(&mut rest)?;
+
+    if !rest.is_empty() {
+      return Err("unexpected bytes after the code".into());
+    }
+
+    let manifest = serde_json::from_slice(manifest).map_err(|err| format!("invalid manifest: {}", err))?;
+
+    Ok(Package { manifest, code: code.to_vec() })
+  }
+}
+
+/// Hash at the end of an encoded package.
+pub fn package_hash(bytes: &[u8]) -> &[u8] {
+  &bytes[bytes.len().saturating_sub(HASH_LEN)..]
+}
+
+// reads a length prefixed field
+fn take<'a>(rest: &mut &'a [u8]) -> Result<&'a [u8], String> {
+  let truncated = || String::from("package is truncated");
+
+  let len = rest.get(..4).ok_or_else(truncated)?;
+  let len = u32::from_le_bytes(len.try_into().unwrap()) as usize;
+  let field = rest.get(4..4 + len).ok_or_else(truncated)?;
+
+  *rest = &rest[4 + len..];
+  Ok(field)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn package() -> Package {
+    let manifest = serde_json::from_str(r#"{ "name": "Token", "version": 2, "methods": [] }"#).unwrap();
+    Package { manifest, code: b"\0asm\x01\0\0\0".to_vec() }
+  }
+
+  // changes the content and puts a matching hash back, so only the content is wrong
+  fn rehash(mut bytes: Vec<u8>) -> Vec<u8> {
+    bytes.truncate(bytes.len() - HASH_LEN);
+    let hash = Sha256::digest(&bytes);
+    bytes.extend_from_slice(&hash);
+    bytes
+  }
+
+  #[test]
+  fn roundtrips() {
+    let bytes = package().encode();
+    let decoded = Package::decode(&bytes).unwrap();
+
+    assert_eq!(decoded.manifest, package().manifest);
+    assert_eq!(decoded.code, package().code);
+    assert_eq!(package_hash(&bytes), Sha256::digest(&bytes[..bytes.len() - HASH_LEN]).as_slice());
+  }
+
+  #[test]
+  fn rejects_corrupted_packages() {
+    let mut bytes = package().encode();
+    let last = bytes.len() - HASH_LEN - 1;
+    bytes[last] ^= 1;
+    assert_eq!(Package::decode(&bytes).err().unwrap(), "hash doesn't match the contents, the package is corrupted");
+
+    let mut bytes = package().encode();
+    *bytes.last_mut().unwrap() ^= 1;
+    assert_eq!(Package::decode(&bytes).err().unwrap(), "hash doesn't match the contents, the package is corrupted");
+  }
+
+  #[test]
+  fn rejects_truncated_packages() {
+    let bytes = package().encode();
+
+    // cut inside the code, the hash is recomputed so only the lengths give it away
+    let mut cut = bytes[..bytes.len() - HASH_LEN - 2].to_vec();
+    cut.extend_from_slice(&[0; HASH_LEN]);
+    assert_eq!(Package::decode(&rehash(cut)).err().unwrap(), "package is truncated");
+
+    assert_eq!(Package::decode(&rehash([MAGIC.to_vec(), vec![FORMAT, 1, 0], vec![0; HASH_LEN]].concat())).err().unwrap(), "package is truncated");
+    assert_eq!(Package::decode(&bytes[..bytes.len() - 1]).err().unwrap(), "hash doesn't match the contents, the package is corrupted");
+    assert_eq!(Package::decode(&bytes[..10]).err().unwrap(), "not a kryolite package");
+  }
+
+  #[test]
+  fn rejects_unknown_formats() {
+    let mut bytes = package().encode();
+    bytes[MAGIC.len()] = FORMAT + 1;
+    assert_eq!(Package::decode(&rehash(bytes)).err().unwrap(), "package format 2 is not supported");
+
+    let mut bytes = package().encode();
+    bytes[0] = b'W';
+    assert_eq!(Package::decode(&rehash(bytes)).err().unwrap(), "not a kryolite package");
+  }
+}
@@ -0,0 +1,324 @@
+use std::fmt;
+
+use kryolite_manifest::MANIFEST_SECTION;
+use wasmparser::{CompositeType, ExternalKind, FuncType, Parser, Payload, TypeRef, ValType};
+
+use ValType::{F32, I32, I64};
+
+// module the SDK declares its imports in
+const HOST_MODULE: &str = "env";
+
+/// Host functions the SDK declares in its `extern "C"` block, with their signatures on wasm32.
+/// This is a copy of the SDK's side, not a list taken from the node, so it only catches contracts
+/// importing something the SDK doesn't declare. Keep in sync with the `extern "C"` block.
+const HOST_FUNCTIONS: &[(&str, &[ValType], &[ValType])] = &[
+  ("__exit", &[I32], &[]),
+  ("__rand", &[], &[F32]),
+  ("__random_seed", &[I32], &[]),
+  ("__remaining_gas", &[], &[I64]),
+  ("__transfer", &[I32, I64], &[]),
+  ("__call", &[I32, I32, I32, I32, I32, I64], &[I32]),
+  ("__balance", &[], &[I64]),
+  ("__balance_of", &[I32], &[I64]),
+  ("__set_owner", &[I32], &[]),
+  ("__upgrade", &[I32, I32], &[]),
+  ("__verify_signature", &[I32, I32, I32, I32], &[I32]),
+  ("__public_key_to_address", &[I32, I32], &[]),
+  ("__transfer_token", &[I32, I32, I32], &[]),
+  ("__consume_token", &[I32, I32], &[]),
+  ("__approval", &[I32, I32, I32], &[]),
+  ("__println", &[I32, I32, I32, I32], &[]),
+  ("__append_event", &[I32, I32, I32, I32], &[]),
+  ("__publish_event", &[], &[]),
+  ("__return", &[I32, I32], &[])
+];
+
+/// Exports the node calls on every contract.
+const REQUIRED_EXPORTS: &[&str] = &["memory", "__init", "__malloc", "__free", "__state", "__state_version"];
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum Problem {
+  Invalid(String),
+  UnknownImport { module: String, name: String },
+  ImportSignature { name: String, expected: String, found: String },
+  /// Only functions can be imported, memory and tables are the contract's own.
+  NonFunctionImport { module: String, name: String },
+  MissingExport(String)
+}
+
+impl fmt::Display for Problem {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Problem::Invalid(err) => write!(f, "not a valid wasm module: {}", err),
+      Problem::UnknownImport { module, name } => write!(f, "imports `{}::{}`, which isn't a host function", module, name),
+      Problem::ImportSignature { name, expected, found } => write!(f, "imports `{}` as {}, the host function is {}", name, found, expected),
+      Problem::NonFunctionImport { module, name } => write!(f, "imports `{}::{}`, only host functions can be imported", module, name),
+      Problem::MissingExport(name) => write!(f, "doesn't export `{}`", name)
+    }
+  }
+}
+
+/// Validates the module and checks its imports against the SDK's host functions and its exports
+/// against what the node calls.
+pub fn check(wasm: &[u8]) -> Result<Vec<String>, Vec<Problem>> {
+  if let Err(err) = wasmparser::validate(wasm) {
+    return Err(vec![Problem::Invalid(err.to_string())]);
+  }
+
+  let mut problems: Vec<Problem> = Vec::new();
+  let mut types: Vec<Option<FuncType>> = Vec::new();
+  let mut exports: Vec<String> = Vec::new();
+
+  for payload in Parser::new(0).parse_all(wasm) {
+    // the module is valid, reading it again can't fail
+    match payload.unwrap() {
+      Payload::TypeSection(reader) => {
+        for group in reader {
+          for ty in group.unwrap().into_types() {
+            types.push(match ty.composite_type {
+              CompositeType::Func(func) => Some(func),
+              _ => None
+            });
+          }
+        }
+      }
+      Payload::ImportSection(reader) => {
+        for import in reader {
+          let import = import.unwrap();
+
+          let ty = match import.ty {
+            TypeRef::Func(index) => types.get(index as usize).cloned().flatten(),
+            _ => {
+              problems.push(Problem::NonFunctionImport { module: import.module.into(), name: import.name.into() });
+              continue;
+            }
+          };
+
+          let host = HOST_FUNCTIONS.iter().find(|(name, ..)| *name == import.name);
+
+          match (host, ty) {
+            (Some((name, params, results)), Some(ty)) if import.module == HOST_MODULE => {
+              if ty.params() != *params || ty.results() != *results {
+                problems.push(Problem::ImportSignature {
+                  name: name.to_string(),
+                  expected: signature(params, results),
+                  found: signature(ty.params(), ty.results())
+                });
+              }
+            }
+            _ => problems.push(Problem::UnknownImport { module: import.module.into(), name: import.name.into() })
+          }
+        }
+      }
+      Payload::ExportSection(reader) => {
+        for export in reader {
+          let export = export.unwrap();
+
+          if matches!(export.kind, ExternalKind::Func | ExternalKind::Memory) {
+            exports.push(export.name.into());
+          }
+        }
+      }
+      _ => ()
+    }
+  }
+
+  for name in REQUIRED_EXPORTS {
+    if !exports.iter().any(|export| export == name) {
+      problems.push(Problem::MissingExport(name.to_string()));
+    }
+  }
+
+  match problems.is_empty() {
+    true => Ok(exports),
+    false => Err(problems)
+  }
+}
+
+fn signature(params: &[ValType], results: &[ValType]) -> String {
+  let names = |types: &[ValType]| types.iter().map(|ty| ty.to_string()).collect::<Vec<String>>().join(", ");
+
+  match results {
+    [] => format!("fn({})", names(params)),
+    _ => format!("fn({}) -> {}", names(params), names(results))
+  }
+}
+
+/// Drops the custom sections the node never reads (names, producers, debug info), the embedded
+/// manifest stays.
+pub fn strip(wasm: &[u8]) -> Vec<u8> {
+  // magic and version
+  let mut stripped = wasm[..8].to_vec();
+  let mut pos = 8;
+
+  while pos < wasm.len() {
+    let id = wasm[pos];
+    let (size, size_len) = read_leb128(&wasm[pos + 1..]);
+    let end = pos + 1 + size_len + size as usize;
+
+    if id != 0 || custom_section_name(&wasm[pos + 1 + size_len..end]) == MANIFEST_SECTION.as_bytes() {
+      stripped.extend_from_slice(&wasm[pos..end]);
+    }
+
+    pos = end;
+  }
+
+  stripped
+}
+
+fn custom_section_name(contents: &[u8]) -> &[u8] {
+  let (len, len_size) = read_leb128(contents);
+  contents.get(len_size..len_size + len as usize).unwrap_or_default()
+}
+
+// section sizes are unsigned LEB128, returns the value and how many bytes it took
+fn read_leb128(bytes: &[u8]) -> (u32, usize) {
+  let mut value: u32 = 0;
+
+  for (i, byte) in bytes.iter().enumerate() {
+    value |= ((byte & 0x7f) as u32) << (7 * i);
+
+    if byte & 0x80 == 0 {
+      return (value, i + 1);
+    }
+  }
+
+  (value, bytes.len())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  // (i32) -> () is type 1, (i64) -> () type 2, every defined function is () -> ()
+  const TYPES: &[&[u8]] = &[&[0x60, 0, 0], &[0x60, 1, 0x7f, 0], &[0x60, 1, 0x7e, 0]];
+
+  const EXPORTS: &[&str] = &["memory", "__init", "__malloc", "__free", "__state", "__state_version", "mint"];
+
+  fn leb128(mut value: usize, out: &mut Vec<u8>) {
+    loop {
+      let byte = (value & 0x7f) as u8;
+      value >>= 7;
+
+      match value {
+        0 => return out.push(byte),
+        _ => out.push(byte | 0x80)
+      }
+    }
+  }
+
+  fn name(text: &str) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    leb128(text.len(), &mut bytes);
+    bytes.extend_from_slice(text.as_bytes());
+    bytes
+  }
+
+  fn section(id: u8, contents: Vec<u8>) -> Vec<u8> {
+    let mut bytes = vec![id];
+    leb128(contents.len(), &mut bytes);
+    bytes.extend(contents);
+    bytes
+  }
+
+  fn vector(items: Vec<Vec<u8>>) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    leb128(items.len(), &mut bytes);
+    bytes.extend(items.concat());
+    bytes
+  }
+
+  fn custom(section_name: &str, data: &[u8]) -> Vec<u8> {
+    section(0, [name(section_name), data.to_vec()].concat())
+  }
+
+  // imports are (module, name, import description), exports are functions except `memory`
+  fn module(imports: &[(&str, &str, &[u8])], exports: &[&str]) -> Vec<u8> {
+    let functions = exports.iter().filter(|export| **export != "memory").count();
+    let imported = imports.iter().filter(|(.., desc)| desc[0] == 0).count();
+
+    let exports = exports.iter().scan(imported, |index, export| Some(match *export {
+      "memory" => [name(export), vec![2, 0]].concat(),
+      _ => {
+        *index += 1;
+        [name(export), vec![0, *index as u8 - 1]].concat()
+      }
+    }));
+
+    [
+      b"\0asm\x01\0\0\0".to_vec(),
+      section(1, vector(TYPES.iter().map(|ty| ty.to_vec()).collect())),
+      section(2, vector(imports.iter().map(|(module, field, desc)| [name(module), name(field), desc.to_vec()].concat()).collect())),
+      section(3, vector(vec![vec![0]; functions])),
+      section(5, vector(vec![vec![0, 1]])),
+      section(7, vector(exports.collect())),
+      section(10, vector(vec![vec![2, 0, 0x0b]; functions]))
+    ].concat()
+  }
+
+  #[test]
+  fn accepts_a_contract() {
+    let wasm = module(&[("env", "__exit", &[0, 1])], EXPORTS);
+    assert_eq!(check(&wasm), Ok(EXPORTS.iter().map(|export| export.to_string()).collect()));
+  }
+
+  #[test]
+  fn rejects_unknown_imports() {
+    let wasm = module(&[("env", "__mint", &[0, 0]), ("wasi", "__exit", &[0, 1])], EXPORTS);
+
+    assert_eq!(check(&wasm), Err(vec![
+      Problem::UnknownImport { module: "env".into(), name: "__mint".into() },
+      Problem::UnknownImport { module: "wasi".into(), name: "__exit".into() }
+    ]));
+  }
+
+  #[test]
+  fn rejects_wrong_signatures() {
+    let wasm = module(&[("env", "__exit", &[0, 2]), ("env", "__publish_event", &[0, 1])], EXPORTS);
+
+    assert_eq!(check(&wasm), Err(vec![
+      Problem::ImportSignature { name: "__exit".into(), expected: "fn(i32)".into(), found: "fn(i64)".into() },
+      Problem::ImportSignature { name: "__publish_event".into(), expected: "fn()".into(), found: "fn(i32)".into() }
+    ]));
+  }
+
+  #[test]
+  fn rejects_non_function_imports() {
+    let wasm = module(&[("env", "__table", &[1, 0x70, 0, 0])], EXPORTS);
+    assert_eq!(check(&wasm), Err(vec![Problem::NonFunctionImport { module: "env".into(), name: "__table".into() }]));
+  }
+
+  #[test]
+  fn rejects_missing_exports() {
+    let wasm = module(&[], &["__init", "__malloc", "__free", "__state"]);
+
+    assert_eq!(check(&wasm), Err(vec![
+      Problem::MissingExport("memory".into()),
+      Problem::MissingExport("__state_version".into())
+    ]));
+  }
+
+  #[test]
+  fn rejects_invalid_modules() {
+    assert!(matches!(check(b"\0asm").unwrap_err()[..], [Problem::Invalid(_)]));
+    assert!(matches!(check(&module(&[], EXPORTS)[..40]).unwrap_err()[..], [Problem::Invalid(_)]));
+  }
+
+  #[test]
+  fn strip_keeps_only_the_manifest() {
+    let wasm = module(&[("env", "__exit", &[0, 1])], EXPORTS);
+    let manifest = custom(MANIFEST_SECTION, br#"{"name":"Token","methods":[]}"#);
+    let names = custom("name", &[1, 2, 3]);
+    // bigger than 127 bytes, so the size takes two bytes
+    let debug = custom(".debug_info", &[0; 300]);
+
+    let full = [wasm.clone(), names.clone(), manifest.clone(), debug, custom("producers", &[0])].concat();
+    let stripped = strip(&full);
+
+    assert_eq!(stripped, [wasm.clone(), manifest].concat());
+    assert!(check(&stripped).is_ok());
+
+    // a section named like the manifest but longer isn't it
+    assert_eq!(strip(&[wasm.clone(), custom("kryolite_manifest_old", &[1])].concat()), wasm);
+  }
+}
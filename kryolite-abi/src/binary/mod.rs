@@ -0,0 +1,292 @@
+//! The compact encoding of `#[smart_contract(abi = "binary")]`, see `kryolite_smart_contract::abi_encode`.
+//! Values go in and come out as the JSON the contract would use for them.
+
+use serde_json::{Map, Number, Value};
+
+use crate::{json, AbiError, Type};
+
+pub fn encode(ty: &Type, value: &Value, path: &str, out: &mut Vec<u8>) -> Result<(), AbiError> {
+  match ty {
+    Type::Unit => json::check(ty, value, path),
+    Type::Bool => {
+      let value = value.as_bool().ok_or_else(|| json::mismatch(ty, value, path))?;
+      out.push(value as u8);
+      Ok(())
+    }
+    Type::Int { .. } | Type::Usize | Type::Isize => {
+      let (negative, magnitude) = json::integer(ty, value, path)?;
+
+      let bits = match ty {
+        // serde writes them as 64 bits
+        Type::Usize | Type::Isize => 64,
+        _ => json::int_layout(ty).1
+      };
+
+      let twos = match negative {
+        true => (!magnitude).wrapping_add(1),
+        false => magnitude
+      };
+
+      out.extend_from_slice(&twos.to_le_bytes()[..bits as usize / 8]);
+      Ok(())
+    }
+    Type::Float { bits } => {
+      let value = value.as_f64().ok_or_else(|| json::mismatch(ty, value, path))?;
+
+      match bits {
+        32 => out.extend_from_slice(&(value as f32).to_le_bytes()),
+        _ => out.extend_from_slice(&value.to_le_bytes())
+      }
+
+      Ok(())
+    }
+    Type::Char => {
+      let value = json::char_value(ty, value, path)?;
+      out.extend_from_slice(&(value as u32).to_le_bytes());
+      Ok(())
+    }
+    Type::String => {
+      let value = value.as_str().ok_or_else(|| json::mismatch(ty, value, path))?;
+      write_len(value.len(), path, out)?;
+      out.extend_from_slice(value.as_bytes());
+      Ok(())
+    }
+    Type::Address => {
+      out.extend_from_slice(&json::address(value, path)?);
+      Ok(())
+    }
+    Type::U256 => {
+      out.extend_from_slice(&json::u256(value, path)?);
+      Ok(())
+    }
+    Type::Kryo => {
+      out.extend_from_slice(&json::kryo_units(value, path)?.to_le_bytes());
+      Ok(())
+    }
+    Type::Option(inner) => match value {
+      Value::Null => {
+        out.push(0);
+        Ok(())
+      }
+      value => {
+        out.push(1);
+        encode(inner, value, path, out)
+      }
+    },
+    Type::Seq(inner) => {
+      let items = value.as_array().ok_or_else(|| json::mismatch(ty, value, path))?;
+      write_len(items.len(), path, out)?;
+
+      for (i, item) in items.iter().enumerate() {
+        encode(inner, item, &format!("{}[{}]", path, i), out)?;
+      }
+
+      Ok(())
+    }
+    Type::Array(inner, len) => {
+      for (i, item) in json::sized_array(ty, value, *len, path)?.iter().enumerate() {
+        encode(inner, item, &format!("{}[{}]", path, i), out)?;
+      }
+
+      Ok(())
+    }
+    Type::Tuple(types) => {
+      let items = json::sized_array(ty, value, types.len(), path)?;
+
+      for (i, (ty, item)) in types.iter().zip(items).enumerate() {
+        encode(ty, item, &format!("{}.{}", path, i), out)?;
+      }
+
+      Ok(())
+    }
+    Type::Map(key, inner) => {
+      let entries = json::object(ty, value, path)?;
+      write_len(entries.len(), path, out)?;
+
+      let mut encoded: Vec<(Vec<u8>, Vec<u8>)> = Vec::with_capacity(entries.len());
+
+      for (name, item) in entries {
+        let path = format!("{}[{:?}]", path, name);
+        let key_value = json::map_key(key, name);
+
+        let mut entry = Vec::new();
+        encode(key, &key_value, &path, &mut entry)?;
+        encode(inner, item, &path, &mut entry)?;
+
+        encoded.push((sort_key(key, &key_value, &path)?, entry));
+      }
+
+      // the contract's maps iterate in key order, JSON objects in the order of the key text
+      encoded.sort_by(|a, b| a.0.cmp(&b.0));
+
+      for (_, entry) in encoded {
+        out.extend_from_slice(&entry);
+      }
+
+      Ok(())
+    }
+    Type::Named(name) => Err(AbiError::Unsupported { path: path.into(), type_name: name.clone() })
+  }
+}
+
+/// Reads one value of `ty` from the front of `input`.
+pub fn decode(ty: &Type, input: &mut &[u8], path: &str) -> Result<Value, AbiError> {
+  Ok(match ty {
+    Type::Unit => Value::Null,
+    Type::Bool => match take(input, 1, path)?[0] {
+      0 => Value::Bool(false),
+      1 => Value::Bool(true),
+      byte => return Err(invalid(path, format!("invalid bool {}", byte)))
+    },
+    Type::Int { .. } | Type::Usize | Type::Isize => {
+      let (signed, bits) = match ty {
+        Type::Usize => (false, 64),
+        Type::Isize => (true, 64),
+        _ => json::int_layout(ty)
+      };
+
+      let bytes = take(input, bits as usize / 8, path)?;
+      let negative = signed && bytes[bytes.len() - 1] & 0x80 != 0;
+
+      // sign extend to 128 bits
+      let mut wide = [if negative { 0xff } else { 0 }; 16];
+      wide[..bytes.len()].copy_from_slice(bytes);
+
+      match (signed, bits) {
+        (true, 128) => Value::String(i128::from_le_bytes(wide).to_string()),
+        (false, 128) => Value::String(u128::from_le_bytes(wide).to_string()),
+        (true, _) => Value::Number((i128::from_le_bytes(wide) as i64).into()),
+        (false, _) => Value::Number((u128::from_le_bytes(wide) as u64).into())
+      }
+    }
+    Type::Float { bits } => {
+      let value = match bits {
+        32 => f32::from_le_bytes(array(input, path)?) as f64,
+        _ => f64::from_le_bytes(array(input, path)?)
+      };
+
+      Number::from_f64(value).map(Value::Number).unwrap_or(Value::Null)
+    }
+    Type::Char => {
+      let value = u32::from_le_bytes(array(input, path)?);
+      let value = char::from_u32(value).ok_or_else(|| invalid(path, format!("invalid char {:#x}", value)))?;
+      Value::String(value.to_string())
+    }
+    Type::String => {
+      let len = read_len(input, path)?;
+      let bytes = take(input, len, path)?;
+      let text = std::str::from_utf8(bytes).map_err(|_| invalid(path, "string is not valid utf-8".into()))?;
+      Value::String(text.into())
+    }
+    Type::Address => Value::String(json::address_string(take(input, json::ADDRESS_LEN, path)?)),
+    Type::U256 => Value::String(json::u256_string(take(input, json::U256_LEN, path)?)),
+    Type::Kryo => Value::String(json::kryo_string(u64::from_le_bytes(array(input, path)?))),
+    Type::Option(inner) => match take(input, 1, path)?[0] {
+      0 => Value::Null,
+      1 => decode(inner, input, path)?,
+      byte => return Err(invalid(path, format!("invalid option tag {}", byte)))
+    },
+    Type::Seq(inner) => {
+      let len = read_len(input, path)?;
+      let mut items = Vec::new();
+
+      for i in 0..len {
+        items.push(decode(inner, input, &format!("{}[{}]", path, i))?);
+      }
+
+      Value::Array(items)
+    }
+    Type::Array(inner, len) => {
+      let mut items = Vec::with_capacity(*len);
+
+      for i in 0..*len {
+        items.push(decode(inner, input, &format!("{}[{}]", path, i))?);
+      }
+
+      Value::Array(items)
+    }
+    Type::Tuple(types) => {
+      let mut items = Vec::with_capacity(types.len());
+
+      for (i, ty) in types.iter().enumerate() {
+        items.push(decode(ty, input, &format!("{}.{}", path, i))?);
+      }
+
+      Value::Array(items)
+    }
+    Type::Map(key, inner) => {
+      let len = read_len(input, path)?;
+      let mut entries = Map::new();
+
+      for i in 0..len {
+        let name = match decode(key, input, &format!("{}.key{}", path, i))? {
+          Value::String(name) => name,
+          // numbers and bools become object keys as text, like serde_json writes them
+          name => name.to_string()
+        };
+
+        let item = decode(inner, input, &format!("{}[{:?}]", path, name))?;
+        entries.insert(name, item);
+      }
+
+      Value::Object(entries)
+    }
+    Type::Named(name) => return Err(AbiError::Unsupported { path: path.into(), type_name: name.clone() })
+  })
+}
+
+// bytes that compare like the key does in the contract, `Ord` of the Rust type
+fn sort_key(ty: &Type, value: &Value, path: &str) -> Result<Vec<u8>, AbiError> {
+  Ok(match ty {
+    Type::Int { .. } | Type::Usize | Type::Isize => {
+      let (signed, _) = json::int_layout(ty);
+      let (negative, magnitude) = json::integer(ty, value, path)?;
+
+      // two's complement with the sign bit flipped sorts like the number
+      let twos = match negative {
+        true => (!magnitude).wrapping_add(1),
+        false => magnitude
+      };
+
+      match signed {
+        true => (twos ^ 1 << 127).to_be_bytes().to_vec(),
+        false => twos.to_be_bytes().to_vec()
+      }
+    }
+    Type::Char => (json::char_value(ty, value, path)? as u32).to_be_bytes().to_vec(),
+    Type::Kryo => json::kryo_units(value, path)?.to_be_bytes().to_vec(),
+    Type::Address => json::address(value, path)?,
+    Type::U256 => json::u256(value, path)?,
+    Type::Bool => vec![value.as_bool().unwrap_or_default() as u8],
+    // strings sort by their bytes either way
+    _ => value.as_str().unwrap_or_default().as_bytes().to_vec()
+  })
+}
+
+fn invalid(path: &str, reason: String) -> AbiError {
+  AbiError::Decode { path: path.into(), reason }
+}
+
+fn take<'a>(input: &mut &'a [u8], len: usize, path: &str) -> Result<&'a [u8], AbiError> {
+  if input.len() < len {
+    return Err(invalid(path, format!("unexpected end of input, {} more bytes needed", len - input.len())));
+  }
+
+  let (bytes, rest) = input.split_at(len);
+  *input = rest;
+  Ok(bytes)
+}
+
+fn array<const N: usize>(input: &mut &[u8], path: &str) -> Result<[u8; N], AbiError> {
+  Ok(take(input, N, path)?.try_into().unwrap())
+}
+
+fn read_len(input: &mut &[u8], path: &str) -> Result<usize, AbiError> {
+  Ok(u32::from_le_bytes(array(input, path)?) as usize)
+}
+
+fn write_len(len: usize, path: &str, out: &mut Vec<u8>) -> Result<(), AbiError> {
+  let len = u32::try_from(len).map_err(|_| invalid(path, "length does not fit in u32".into()))?;
+  out.extend_from_slice(&len.to_le_bytes());
+  Ok(())
+}
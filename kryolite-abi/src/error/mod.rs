@@ -0,0 +1,46 @@
+use std::fmt;
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum AbiError {
+  UnknownMethod(String),
+  UnknownEvent(String),
+  /// Type name in the manifest that isn't valid Rust.
+  InvalidType(String),
+  /// Arguments have to be a JSON array in parameter order or an object keyed by parameter name.
+  InvalidArguments { method: String, found: String },
+  ArgumentCount { method: String, expected: usize, found: usize },
+  MissingArgument { method: String, param: String },
+  UnknownArgument { method: String, param: String },
+  /// `path` points at the value, e.g. `buy_tickets(tickets)[2].owner`.
+  Mismatch { path: String, expected: String, found: String },
+  /// Contract types only have a name in the manifest, without their fields they can't be
+  /// written in the binary encoding.
+  Unsupported { path: String, type_name: String },
+  Decode { path: String, reason: String },
+  EventParams { event: String, expected: usize, found: usize },
+  /// Bindings only write the JSON abi, the contract has no method using it.
+  BinaryBindings(String),
+  Manifest(String)
+}
+
+impl fmt::Display for AbiError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      AbiError::UnknownMethod(name) => write!(f, "the contract has no method `{}`", name),
+      AbiError::UnknownEvent(name) => write!(f, "the contract has no event `{}`", name),
+      AbiError::InvalidType(name) => write!(f, "`{}` is not a type", name),
+      AbiError::InvalidArguments { method, found } => write!(f, "arguments of `{}` must be an array or an object, found {}", method, found),
+      AbiError::ArgumentCount { method, expected, found } => write!(f, "`{}` takes {} arguments, found {}", method, expected, found),
+      AbiError::MissingArgument { method, param } => write!(f, "missing argument `{}` of `{}`", param, method),
+      AbiError::UnknownArgument { method, param } => write!(f, "`{}` has no parameter `{}`", method, param),
+      AbiError::Mismatch { path, expected, found } => write!(f, "{}: expected {}, found {}", path, expected, found),
+      AbiError::Unsupported { path, type_name } => write!(f, "{}: `{}` can't be encoded in the binary abi, the manifest doesn't describe its fields", path, type_name),
+      AbiError::Decode { path, reason } => write!(f, "{}: {}", path, reason),
+      AbiError::EventParams { event, expected, found } => write!(f, "event `{}` has {} parameters, found {}", event, expected, found),
+      AbiError::BinaryBindings(name) => write!(f, "`{}` only has binary abi methods, bindings can only be generated for json abi methods", name),
+      AbiError::Manifest(err) => write!(f, "invalid manifest: {}", err)
+    }
+  }
+}
+
+impl std::error::Error for AbiError {}
@@ -0,0 +1,65 @@
+//! Events published with `event!(Name, ...)`. The host records every appended value as the name
+//! of its type and its raw bytes, the first one being the name of the event as a `str`.
+
+use serde_json::{Number, Value};
+
+use crate::{json, AbiError};
+
+/// Value of a published event, as the host recorded it.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct EventParam {
+  pub type_name: String,
+  pub bytes: Vec<u8>
+}
+
+impl EventParam {
+  pub fn new(type_name: &str, bytes: &[u8]) -> EventParam {
+    EventParam {
+      type_name: type_name.into(),
+      bytes: bytes.to_vec()
+    }
+  }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct DecodedEvent {
+  pub name: String,
+  /// Parameters after the name, as JSON in the same form as method arguments.
+  pub values: Vec<Value>
+}
+
+pub(crate) fn decode_param(param: &EventParam, path: &str) -> Result<Value, AbiError> {
+  // numbers are recorded as references, `&u64`
+  let type_name = param.type_name.trim_start_matches('&');
+  let bytes = param.bytes.as_slice();
+
+  let invalid = || AbiError::Decode {
+    path: path.into(),
+    reason: format!("{} bytes are not a valid {}", bytes.len(), type_name)
+  };
+
+  let value = match type_name {
+    "str" => Value::String(std::str::from_utf8(bytes).map_err(|_| invalid())?.into()),
+    "Address" if bytes.len() == json::ADDRESS_LEN => Value::String(json::address_string(bytes)),
+    "U256" if bytes.len() == json::U256_LEN => Value::String(json::u256_string(bytes)),
+    "bool" if bytes.len() == 1 => Value::Bool(bytes[0] != 0),
+    "f32" => Number::from_f64(f32::from_le_bytes(bytes.try_into().map_err(|_| invalid())?) as f64).map(Value::Number).unwrap_or(Value::Null),
+    "f64" => Number::from_f64(f64::from_le_bytes(bytes.try_into().map_err(|_| invalid())?)).map(Value::Number).unwrap_or(Value::Null),
+    // usize is as wide as the contract's pointers, so go by the bytes
+    "u8" | "u16" | "u32" | "u64" | "usize" if (1..=8).contains(&bytes.len()) => {
+      let mut wide = [0; 8];
+      wide[..bytes.len()].copy_from_slice(bytes);
+      Value::Number(u64::from_le_bytes(wide).into())
+    }
+    "i8" | "i16" | "i32" | "i64" | "isize" if (1..=8).contains(&bytes.len()) => {
+      let negative = bytes[bytes.len() - 1] & 0x80 != 0;
+      let mut wide = [if negative { 0xff } else { 0 }; 8];
+      wide[..bytes.len()].copy_from_slice(bytes);
+      Value::Number(i64::from_le_bytes(wide).into())
+    }
+    "Address" | "U256" | "bool" | "u8" | "u16" | "u32" | "u64" | "usize" | "i8" | "i16" | "i32" | "i64" | "isize" => return Err(invalid()),
+    _ => return Err(AbiError::Decode { path: path.into(), reason: format!("events can't carry `{}`", type_name) })
+  };
+
+  Ok(value)
+}
@@ -0,0 +1,304 @@
+//! Values as the contract reads and writes them in JSON, which is also how they are handed to
+//! and returned from this crate in either abi.
+
+use data_encoding::Encoding;
+use data_encoding_macro::new_encoding;
+use serde_json::Value;
+
+use crate::{AbiError, Type};
+
+// same alphabet as `kryolite_smart_contract::B32`
+const B32: Encoding = new_encoding!{
+    symbols: "abcdefghijkmnpqrstuvwxyz23456789",
+};
+
+const ADDRESS_PREFIX: &str = "kryo:";
+pub(crate) const ADDRESS_LEN: usize = 26;
+pub(crate) const U256_LEN: usize = 32;
+const KRYO_DECIMALS: usize = 6;
+
+/// Checks that `value` is what the contract expects for `ty`.
+pub fn check(ty: &Type, value: &Value, path: &str) -> Result<(), AbiError> {
+  match ty {
+    Type::Unit => match value {
+      Value::Null => Ok(()),
+      _ => Err(mismatch(ty, value, path))
+    },
+    Type::Bool => value.as_bool().map(|_| ()).ok_or_else(|| mismatch(ty, value, path)),
+    Type::Int { .. } | Type::Usize | Type::Isize => integer(ty, value, path).map(|_| ()),
+    Type::Float { .. } => value.as_f64().map(|_| ()).ok_or_else(|| mismatch(ty, value, path)),
+    Type::Char => char_value(ty, value, path).map(|_| ()),
+    Type::String => value.as_str().map(|_| ()).ok_or_else(|| mismatch(ty, value, path)),
+    Type::Address => address(value, path).map(|_| ()),
+    Type::U256 => u256(value, path).map(|_| ()),
+    Type::Kryo => kryo_units(value, path).map(|_| ()),
+    Type::Option(inner) => match value {
+      Value::Null => Ok(()),
+      value => check(inner, value, path)
+    },
+    Type::Seq(inner) => {
+      for (i, item) in array(ty, value, path)?.iter().enumerate() {
+        check(inner, item, &format!("{}[{}]", path, i))?;
+      }
+
+      Ok(())
+    }
+    Type::Array(inner, len) => {
+      let items = sized_array(ty, value, *len, path)?;
+
+      for (i, item) in items.iter().enumerate() {
+        check(inner, item, &format!("{}[{}]", path, i))?;
+      }
+
+      Ok(())
+    }
+    Type::Tuple(types) => {
+      let items = sized_array(ty, value, types.len(), path)?;
+
+      for (i, (ty, item)) in types.iter().zip(items).enumerate() {
+        check(ty, item, &format!("{}.{}", path, i))?;
+      }
+
+      Ok(())
+    }
+    Type::Map(key, inner) => {
+      for (name, item) in object(ty, value, path)? {
+        let path = format!("{}[{:?}]", path, name);
+        check(key, &map_key(key, name), &path)?;
+        check(inner, item, &path)?;
+      }
+
+      Ok(())
+    }
+    // nothing to check it against
+    Type::Named(_) => Ok(())
+  }
+}
+
+/// Writes a checked `value` as the contract reads it. Same as the JSON itself, except 128 bit
+/// integers given as strings go out as numbers.
+pub(crate) fn write(ty: &Type, value: &Value, out: &mut String) {
+  match (ty, value) {
+    // written from the parsed value, the string may have a `+` or leading zeros JSON doesn't allow
+    (Type::Int { bits: 128, .. }, Value::String(_)) => {
+      let (negative, magnitude) = integer(ty, value, "").unwrap_or_default();
+
+      if negative && magnitude > 0 {
+        out.push('-');
+      }
+
+      out.push_str(&magnitude.to_string());
+    }
+    (Type::Option(inner), value) if !value.is_null() => write(inner, value, out),
+    (Type::Seq(inner) | Type::Array(inner, _), Value::Array(items)) => write_list(items.iter().map(|item| (&**inner, item)), out),
+    (Type::Tuple(types), Value::Array(items)) => write_list(types.iter().zip(items), out),
+    (Type::Map(_, inner), Value::Object(entries)) => {
+      out.push('{');
+
+      for (i, (name, item)) in entries.iter().enumerate() {
+        if i > 0 {
+          out.push(',');
+        }
+
+        out.push_str(&Value::String(name.clone()).to_string());
+        out.push(':');
+        write(inner, item, out);
+      }
+
+      out.push('}');
+    }
+    _ => out.push_str(&value.to_string())
+  }
+}
+
+pub(crate) fn write_list<'a>(items: impl Iterator<Item = (&'a Type, &'a Value)>, out: &mut String) {
+  out.push('[');
+
+  for (i, (ty, item)) in items.enumerate() {
+    if i > 0 {
+      out.push(',');
+    }
+
+    write(ty, item, out);
+  }
+
+  out.push(']');
+}
+
+pub(crate) fn mismatch(ty: &Type, value: &Value, path: &str) -> AbiError {
+  AbiError::Mismatch { path: path.into(), expected: expected(ty), found: describe(value) }
+}
+
+// what the JSON for a type looks like, for error messages
+fn expected(ty: &Type) -> String {
+  match ty {
+    Type::Unit => "null".into(),
+    Type::Int { .. } | Type::Usize | Type::Isize => format!("{} (a whole number)", ty),
+    Type::Char => "char (a string of one character)".into(),
+    Type::Address => format!("Address (a string like \"{}...\")", ADDRESS_PREFIX),
+    Type::U256 => "U256 (a base32 string of 32 bytes)".into(),
+    Type::Kryo => "Kryo (an amount string like \"1.5\")".into(),
+    Type::Seq(_) | Type::Array(..) | Type::Tuple(_) => format!("{} (an array)", ty),
+    Type::Map(..) => format!("{} (an object)", ty),
+    _ => ty.to_string()
+  }
+}
+
+pub(crate) fn describe(value: &Value) -> String {
+  let json = value.to_string();
+
+  let json = match json.chars().count() > 40 {
+    true => format!("{}...", json.chars().take(40).collect::<String>()),
+    false => json
+  };
+
+  let kind = match value {
+    Value::Null => return "null".into(),
+    Value::Bool(_) => "bool",
+    Value::Number(_) => "number",
+    Value::String(_) => "string",
+    Value::Array(_) => "array",
+    Value::Object(_) => "object"
+  };
+
+  format!("{} {}", kind, json)
+}
+
+fn array<'a>(ty: &Type, value: &'a Value, path: &str) -> Result<&'a Vec<Value>, AbiError> {
+  value.as_array().ok_or_else(|| mismatch(ty, value, path))
+}
+
+pub(crate) fn sized_array<'a>(ty: &Type, value: &'a Value, len: usize, path: &str) -> Result<&'a Vec<Value>, AbiError> {
+  let items = array(ty, value, path)?;
+
+  match items.len() == len {
+    true => Ok(items),
+    false => Err(AbiError::Mismatch { path: path.into(), expected: format!("{} ({} items)", ty, len), found: format!("{} items", items.len()) })
+  }
+}
+
+pub(crate) fn object<'a>(ty: &Type, value: &'a Value, path: &str) -> Result<&'a serde_json::Map<String, Value>, AbiError> {
+  value.as_object().ok_or_else(|| mismatch(ty, value, path))
+}
+
+/// JSON object keys are strings, this is the key as the value of its type.
+pub(crate) fn map_key(key: &Type, name: &str) -> Value {
+  match key {
+    Type::Bool => name.parse().map(Value::Bool).unwrap_or_else(|_| name.into()),
+    // exact with arbitrary_precision, 128 bit keys can stay strings
+    Type::Int { .. } | Type::Usize | Type::Isize => match name.parse::<serde_json::Number>() {
+      Ok(number) => Value::Number(number),
+      Err(_) => name.into()
+    },
+    _ => name.into()
+  }
+}
+
+/// Integers as sign and magnitude, 128 bit values may also be strings since most JSON
+/// libraries stop at 64 bits. Other widths have to be numbers.
+pub(crate) fn integer(ty: &Type, value: &Value, path: &str) -> Result<(bool, u128), AbiError> {
+  let (signed, bits) = int_layout(ty);
+
+  let parsed = match value {
+    Value::Number(number) => match (number.as_u64(), number.as_i64()) {
+      (Some(n), _) => Some((false, n as u128)),
+      (_, Some(n)) => Some((n < 0, n.unsigned_abs() as u128)),
+      // larger than 64 bits, kept as its text by arbitrary_precision
+      _ => parse_digits(&number.to_string())
+    },
+    Value::String(digits) if bits == 128 => parse_digits(digits),
+    _ => None
+  };
+
+  let (negative, magnitude) = parsed.ok_or_else(|| mismatch(ty, value, path))?;
+
+  let fits = match (signed, negative) {
+    (false, true) => magnitude == 0,
+    (false, false) => bits == 128 || magnitude < 1 << bits,
+    (true, true) => magnitude <= 1 << (bits - 1),
+    (true, false) => magnitude < 1 << (bits - 1)
+  };
+
+  match fits {
+    true => Ok((negative, magnitude)),
+    false => Err(AbiError::Mismatch { path: path.into(), expected: format!("a value that fits in {}", ty), found: value.to_string() })
+  }
+}
+
+fn parse_digits(text: &str) -> Option<(bool, u128)> {
+  match text.strip_prefix('-') {
+    Some(digits) if digits.starts_with('+') => None,
+    Some(digits) => digits.parse::<u128>().ok().map(|n| (true, n)),
+    None => text.parse::<u128>().ok().map(|n| (false, n))
+  }
+}
+
+/// Sign and range of an integer type as the contract sees it.
+pub(crate) fn int_layout(ty: &Type) -> (bool, u32) {
+  match ty {
+    Type::Int { signed, bits } => (*signed, *bits),
+    Type::Usize => (false, 32),
+    Type::Isize => (true, 32),
+    _ => unreachable!("{} is not an integer", ty)
+  }
+}
+
+pub(crate) fn char_value(ty: &Type, value: &Value, path: &str) -> Result<char, AbiError> {
+  let mut chars = value.as_str().map(|text| text.chars()).ok_or_else(|| mismatch(ty, value, path))?;
+
+  match (chars.next(), chars.next()) {
+    (Some(c), None) => Ok(c),
+    _ => Err(mismatch(ty, value, path))
+  }
+}
+
+pub(crate) fn address(value: &Value, path: &str) -> Result<Vec<u8>, AbiError> {
+  let bytes = value.as_str()
+    .and_then(|text| text.strip_prefix(ADDRESS_PREFIX))
+    .and_then(|text| B32.decode(text.as_bytes()).ok());
+
+  match bytes {
+    Some(bytes) if bytes.len() == ADDRESS_LEN => Ok(bytes),
+    _ => Err(mismatch(&Type::Address, value, path))
+  }
+}
+
+pub(crate) fn address_string(bytes: &[u8]) -> String {
+  format!("{}{}", ADDRESS_PREFIX, B32.encode(bytes))
+}
+
+pub(crate) fn u256(value: &Value, path: &str) -> Result<Vec<u8>, AbiError> {
+  match value.as_str().and_then(|text| B32.decode(text.as_bytes()).ok()) {
+    Some(bytes) if bytes.len() == U256_LEN => Ok(bytes),
+    _ => Err(mismatch(&Type::U256, value, path))
+  }
+}
+
+pub(crate) fn u256_string(bytes: &[u8]) -> String {
+  B32.encode(bytes)
+}
+
+/// Amount in units, from the decimal string `Kryo` is written as.
+pub(crate) fn kryo_units(value: &Value, path: &str) -> Result<u64, AbiError> {
+  let invalid = || mismatch(&Type::Kryo, value, path);
+  let text = value.as_str().ok_or_else(invalid)?;
+
+  let (whole, fraction) = text.split_once('.').unwrap_or((text, ""));
+
+  if whole.is_empty() || !whole.chars().chain(fraction.chars()).all(|c| c.is_ascii_digit()) {
+    return Err(invalid());
+  }
+
+  if fraction.len() > KRYO_DECIMALS {
+    return Err(AbiError::Mismatch { path: path.into(), expected: format!("Kryo with at most {} decimals", KRYO_DECIMALS), found: text.into() });
+  }
+
+  let units = format!("{}{:0<width$}", whole, fraction, width = KRYO_DECIMALS);
+
+  units.parse().map_err(|_| AbiError::Mismatch { path: path.into(), expected: "an amount that fits in Kryo".into(), found: text.into() })
+}
+
+pub(crate) fn kryo_string(units: u64) -> String {
+  let scale = 10u64.pow(KRYO_DECIMALS as u32);
+  format!("{}.{:0width$}", units / scale, units % scale, width = KRYO_DECIMALS)
+}
@@ -0,0 +1,170 @@
+//! Calls to Kryolite contracts from outside the chain: checks and encodes arguments, decodes
+//! return values and events, all driven by the contract's `manifest.json`.
+//!
+//! Values are `serde_json::Value` in the form the contract uses in JSON, whichever abi it was
+//! built with: `Address` is `"kryo:..."`, `U256` its base32 string and `Kryo` an amount like
+//! `"1.5"`. 128 bit integers may be strings as well as numbers.
+//!
+//! [`typescript`] writes the same types out as bindings for the frontend.
+
+mod binary;
+mod error;
+mod events;
+mod json;
+mod types;
+mod typescript;
+
+pub use binary::{encode, decode};
+pub use error::*;
+pub use events::{EventParam, DecodedEvent};
+pub use json::check;
+pub use types::*;
+pub use typescript::typescript;
+
+pub use kryolite_manifest::{Abi, Contract, Method};
+
+use serde_json::Value;
+
+/// Arguments of a call, ready for the node.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Params {
+  /// JSON array of the arguments in parameter order.
+  Json(String),
+  /// The arguments as a tuple in the binary encoding.
+  Binary(Vec<u8>)
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Call {
+  pub method: String,
+  pub params: Params
+}
+
+pub struct ContractAbi {
+  manifest: Contract
+}
+
+impl ContractAbi {
+  pub fn new(manifest: Contract) -> ContractAbi {
+    ContractAbi { manifest }
+  }
+
+  pub fn from_json(json: &str) -> Result<ContractAbi, AbiError> {
+    Contract::from_json(json)
+      .map(ContractAbi::new)
+      .map_err(|err| AbiError::Manifest(err.to_string()))
+  }
+
+  pub fn manifest(&self) -> &Contract {
+    &self.manifest
+  }
+
+  fn method(&self, name: &str) -> Result<&Method, AbiError> {
+    self.manifest.method(name).ok_or_else(|| AbiError::UnknownMethod(name.into()))
+  }
+
+  /// Checks `args` against the parameters of `method` and encodes them in the abi of the
+  /// method. `args` is an array in parameter order or an object keyed by parameter name.
+  pub fn encode_call(&self, method: &str, args: &Value) -> Result<Call, AbiError> {
+    let found = self.method(method)?;
+    let args = self.arguments(found, args)?;
+
+    let mut types = Vec::with_capacity(args.len());
+
+    for (param, arg) in found.method_params.iter().zip(&args) {
+      let ty = Type::parse(&param.param_type)?;
+      json::check(&ty, arg, &format!("{}({})", method, param.name))?;
+      types.push(ty);
+    }
+
+    let params = match found.abi {
+      Abi::Json => {
+        let mut json = String::new();
+        json::write_list(types.iter().zip(&args), &mut json);
+        Params::Json(json)
+      }
+      Abi::Binary => {
+        let mut bytes = Vec::new();
+
+        for ((param, ty), arg) in found.method_params.iter().zip(&types).zip(&args) {
+          binary::encode(ty, arg, &format!("{}({})", method, param.name), &mut bytes)?;
+        }
+
+        Params::Binary(bytes)
+      }
+    };
+
+    Ok(Call { method: method.into(), params })
+  }
+
+  // arguments in parameter order
+  fn arguments(&self, method: &Method, args: &Value) -> Result<Vec<Value>, AbiError> {
+    let params = &method.method_params;
+
+    match args {
+      Value::Array(items) if items.len() == params.len() => Ok(items.clone()),
+      Value::Array(items) => Err(AbiError::ArgumentCount { method: method.name.clone(), expected: params.len(), found: items.len() }),
+      // methods without parameters can be called with nothing
+      Value::Null if params.is_empty() => Ok(Vec::new()),
+      Value::Object(named) => {
+        if let Some(unknown) = named.keys().find(|name| !params.iter().any(|param| &param.name == *name)) {
+          return Err(AbiError::UnknownArgument { method: method.name.clone(), param: unknown.clone() });
+        }
+
+        params.iter()
+          .map(|param| named.get(&param.name).cloned().ok_or_else(|| AbiError::MissingArgument { method: method.name.clone(), param: param.name.clone() }))
+          .collect()
+      }
+      other => Err(AbiError::InvalidArguments { method: method.name.clone(), found: json::describe(other) })
+    }
+  }
+
+  /// Reads what `method` returned, JSON or binary depending on the abi of the method.
+  pub fn decode_return(&self, method: &str, bytes: &[u8]) -> Result<Value, AbiError> {
+    let found = self.method(method)?;
+    let ty = Type::parse(&found.return_value.value_type)?;
+    let path = format!("{}()", method);
+
+    if ty == Type::Unit {
+      return Ok(Value::Null);
+    }
+
+    match found.abi {
+      Abi::Json => {
+        let value: Value = serde_json::from_slice(bytes).map_err(|err| AbiError::Decode { path: path.clone(), reason: err.to_string() })?;
+        json::check(&ty, &value, &path)?;
+        Ok(value)
+      }
+      Abi::Binary => {
+        let mut input = bytes;
+        let value = binary::decode(&ty, &mut input, &path)?;
+
+        match input.is_empty() {
+          true => Ok(value),
+          false => Err(AbiError::Decode { path, reason: format!("{} trailing bytes after the value", input.len()) })
+        }
+      }
+    }
+  }
+
+  /// Decodes an event as the host recorded it, the name first and then its parameters.
+  pub fn decode_event(&self, params: &[EventParam]) -> Result<DecodedEvent, AbiError> {
+    let name = match params.first().map(|param| events::decode_param(param, "event")) {
+      Some(Ok(Value::String(name))) => name,
+      _ => return Err(AbiError::Decode { path: "event".into(), reason: "the first parameter must be the name of the event".into() })
+    };
+
+    let event = self.manifest.event(&name).ok_or_else(|| AbiError::UnknownEvent(name.clone()))?;
+
+    if event.param_count != params.len() - 1 {
+      return Err(AbiError::EventParams { event: name, expected: event.param_count, found: params.len() - 1 });
+    }
+
+    let values = params[1..].iter()
+      .enumerate()
+      .map(|(i, param)| events::decode_param(param, &format!("{}[{}]", name, i)))
+      .collect::<Result<Vec<Value>, AbiError>>()?;
+
+    Ok(DecodedEvent { name, values })
+  }
+}
@@ -0,0 +1,175 @@
+use std::fmt;
+
+use crate::AbiError;
+
+/// Parameter or return type of a method, parsed from the type names in the manifest.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Type {
+  /// `()` and methods without a return value.
+  Unit,
+  Bool,
+  Int { signed: bool, bits: u32 },
+  /// 32 bits in the contract, but serde always encodes it as 64.
+  Usize,
+  Isize,
+  Float { bits: u32 },
+  Char,
+  String,
+  Address,
+  U256,
+  Kryo,
+  Option(Box<Type>),
+  /// `Vec`, `OrderedSet` and the other sequences.
+  Seq(Box<Type>),
+  Array(Box<Type>, usize),
+  Tuple(Vec<Type>),
+  /// `OrderedMap`, `IndexedMap` and the other maps.
+  Map(Box<Type>, Box<Type>),
+  /// A type of the contract itself, the manifest has its name but not its fields.
+  Named(String)
+}
+
+impl Type {
+  pub fn parse(name: &str) -> Result<Type, AbiError> {
+    let compact: String = name.chars().filter(|c| !c.is_whitespace()).collect();
+    let mut parser = Parser { input: &compact, pos: 0 };
+
+    let ty = parser.parse().ok_or_else(|| AbiError::InvalidType(name.into()))?;
+
+    match parser.pos == compact.len() {
+      true => Ok(ty),
+      false => Err(AbiError::InvalidType(name.into()))
+    }
+  }
+}
+
+impl fmt::Display for Type {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Type::Unit => write!(f, "()"),
+      Type::Bool => write!(f, "bool"),
+      Type::Int { signed, bits } => write!(f, "{}{}", if *signed { "i" } else { "u" }, bits),
+      Type::Usize => write!(f, "usize"),
+      Type::Isize => write!(f, "isize"),
+      Type::Float { bits } => write!(f, "f{}", bits),
+      Type::Char => write!(f, "char"),
+      Type::String => write!(f, "String"),
+      Type::Address => write!(f, "Address"),
+      Type::U256 => write!(f, "U256"),
+      Type::Kryo => write!(f, "Kryo"),
+      Type::Option(inner) => write!(f, "Option<{}>", inner),
+      Type::Seq(inner) => write!(f, "Vec<{}>", inner),
+      Type::Array(inner, len) => write!(f, "[{}; {}]", inner, len),
+      Type::Tuple(types) => write!(f, "({})", types.iter().map(|ty| ty.to_string()).collect::<Vec<String>>().join(", ")),
+      Type::Map(key, value) => write!(f, "OrderedMap<{}, {}>", key, value),
+      Type::Named(name) => write!(f, "{}", name)
+    }
+  }
+}
+
+struct Parser<'a> {
+  input: &'a str,
+  pos: usize
+}
+
+impl Parser<'_> {
+  fn rest(&self) -> &str {
+    &self.input[self.pos..]
+  }
+
+  fn eat(&mut self, token: &str) -> bool {
+    let found = self.rest().starts_with(token);
+
+    if found {
+      self.pos += token.len();
+    }
+
+    found
+  }
+
+  fn parse(&mut self) -> Option<Type> {
+    if self.eat("(") {
+      let types = self.list(")")?;
+
+      return Some(match types.len() {
+        0 => Type::Unit,
+        _ => Type::Tuple(types)
+      });
+    }
+
+    if self.eat("[") {
+      let inner = self.parse()?;
+
+      if !self.eat(";") {
+        return None;
+      }
+
+      let digits: String = self.rest().chars().take_while(|c| c.is_ascii_digit()).collect();
+      self.pos += digits.len();
+
+      return match self.eat("]") {
+        true => Some(Type::Array(Box::new(inner), digits.parse().ok()?)),
+        false => None
+      };
+    }
+
+    let path: String = self.rest().chars().take_while(|c| c.is_alphanumeric() || *c == '_' || *c == ':').collect();
+    self.pos += path.len();
+
+    // `kryolite_smart_contract::Address` is an Address
+    let name = path.rsplit("::").next().filter(|name| !name.is_empty())?.to_string();
+
+    let args = match self.eat("<") {
+      true => self.list(">")?,
+      false => Vec::new()
+    };
+
+    let arg = |i: usize| Box::new(args[i].clone());
+
+    Some(match (name.as_str(), args.len()) {
+      ("void", 0) => Type::Unit,
+      ("bool", 0) => Type::Bool,
+      ("u8", 0) => Type::Int { signed: false, bits: 8 },
+      ("u16", 0) => Type::Int { signed: false, bits: 16 },
+      ("u32", 0) => Type::Int { signed: false, bits: 32 },
+      ("u64", 0) => Type::Int { signed: false, bits: 64 },
+      ("u128", 0) => Type::Int { signed: false, bits: 128 },
+      ("i8", 0) => Type::Int { signed: true, bits: 8 },
+      ("i16", 0) => Type::Int { signed: true, bits: 16 },
+      ("i32", 0) => Type::Int { signed: true, bits: 32 },
+      ("i64", 0) => Type::Int { signed: true, bits: 64 },
+      ("i128", 0) => Type::Int { signed: true, bits: 128 },
+      ("usize", 0) => Type::Usize,
+      ("isize", 0) => Type::Isize,
+      ("f32", 0) => Type::Float { bits: 32 },
+      ("f64", 0) => Type::Float { bits: 64 },
+      ("char", 0) => Type::Char,
+      ("String" | "str", 0) => Type::String,
+      ("Address", 0) => Type::Address,
+      ("U256", 0) => Type::U256,
+      ("Kryo", 0) => Type::Kryo,
+      ("Box", 1) => *arg(0),
+      ("Option", 1) => Type::Option(arg(0)),
+      ("Vec" | "VecDeque" | "OrderedSet" | "BTreeSet" | "HashSet", 1) => Type::Seq(arg(0)),
+      ("OrderedMap" | "BTreeMap" | "HashMap" | "IndexedMap", 2) => Type::Map(arg(0), arg(1)),
+      (_, 0) => Type::Named(name),
+      // generic types of the contract, written out as they appear in the manifest
+      _ => Type::Named(format!("{}<{}>", name, args.iter().map(|ty| ty.to_string()).collect::<Vec<String>>().join(", ")))
+    })
+  }
+
+  // comma separated types up to `close`
+  fn list(&mut self, close: &str) -> Option<Vec<Type>> {
+    let mut types = Vec::new();
+
+    while !self.eat(close) {
+      types.push(self.parse()?);
+
+      if !self.eat(",") && !self.rest().starts_with(close) {
+        return None;
+      }
+    }
+
+    Some(types)
+  }
+}
@@ -0,0 +1,236 @@
+//! TypeScript bindings for the frontend, generated from the manifest.
+
+use crate::{AbiError, Type};
+use kryolite_manifest::{Abi, Contract, Method};
+
+const PRELUDE: &str = r#"declare const brand: unique symbol;
+
+/** Address of an account or contract, `kryo:` and 42 base32 characters. */
+export type Address = string & { readonly [brand]: "Address" };
+/** 256 bit value as 52 base32 characters. */
+export type U256 = string & { readonly [brand]: "U256" };
+/** Amount with up to 6 decimals, like "1.5". */
+export type Kryo = string;
+
+export function asAddress(value: string): Address {
+  if (!/^kryo:[a-kmnp-z2-9]{42}$/.test(value)) {
+    throw new Error(`not an address: ${value}`);
+  }
+
+  return value as Address;
+}
+
+export function asU256(value: string): U256 {
+  if (!/^[a-kmnp-z2-9]{52}$/.test(value)) {
+    throw new Error(`not a U256: ${value}`);
+  }
+
+  return value as U256;
+}
+
+/** Call of a contract method, `params` are the arguments as the node takes them. */
+export interface Call<R> {
+  method: string;
+  params: string;
+  readonly: boolean;
+  /** Reads the JSON the method returned. */
+  decode(json: string): R;
+}
+
+// 64 and 128 bit integers are bigints, which JSON.stringify can't write as numbers by itself
+function stringify(args: unknown[]): string {
+  return JSON.stringify(args, (_, value) => typeof value === "bigint" ? `\u0000bigint:${value}` : value)
+    .replace(/"\\u0000bigint:(-?\d+)"/g, "$1");
+}
+
+// where the bigints are in a return value, anything else is read as is
+type Shape = "bigint" | "value" | Shape[] | { items: Shape } | { values: Shape };
+
+// JSON.parse rounds integers above 2^53, so every number is read as its text first
+function parse(json: string, shape: Shape): unknown {
+  const quoted = json.replace(/"(?:[^"\\]|\\.)*"|-?\d+(?:\.\d+)?(?:[eE][+-]?\d+)?/g,
+    (token) => token.startsWith('"') ? token : `"\\u0000${token}"`);
+
+  return revive(JSON.parse(quoted), shape);
+}
+
+function revive(value: unknown, shape: Shape): unknown {
+  if (typeof value === "string" && value.startsWith("\u0000")) {
+    return shape === "bigint" ? BigInt(value.slice(1)) : Number(value.slice(1));
+  }
+
+  if (Array.isArray(value)) {
+    return value.map((item, i) => revive(item, Array.isArray(shape) ? shape[i] : typeof shape === "object" && "items" in shape ? shape.items : "value"));
+  }
+
+  if (value !== null && typeof value === "object") {
+    const values = typeof shape === "object" && "values" in shape ? shape.values : "value";
+    return Object.fromEntries(Object.entries(value).map(([key, item]) => [key, revive(item, values)]));
+  }
+
+  return value;
+}
+
+function call<R>(method: string, args: unknown[], readonly: boolean, returns: Shape = "value"): Call<R> {
+  return { method, params: stringify(args), readonly, decode: (json) => parse(json, returns) as R };
+}
+"#;
+
+/// Writes a TypeScript module with the argument and return types of every json abi method of
+/// the contract and a builder for each call, `KryoliteLottery.approve({ to, token_id })`. Binary
+/// abi methods are left out and listed at the top of the module.
+pub fn typescript(contract: &Contract) -> Result<String, AbiError> {
+  let mut methods: Vec<&Method> = Vec::new();
+  let mut binary: Vec<&str> = Vec::new();
+
+  // identical duplicates are allowed in the manifest
+  for method in &contract.methods {
+    match method.abi {
+      Abi::Binary if !binary.contains(&method.name.as_str()) => binary.push(&method.name),
+      Abi::Json if !methods.iter().any(|seen| seen.name == method.name) => methods.push(method),
+      _ => ()
+    }
+  }
+
+  if methods.is_empty() && !binary.is_empty() {
+    return Err(AbiError::BinaryBindings(contract.name.clone()));
+  }
+
+  let mut writer = Writer { named: Vec::new() };
+  let mut types = String::new();
+  let mut calls = String::new();
+
+  for method in &methods {
+    let name = pascal_case(&method.name);
+    let mut args = Vec::new();
+
+    if !method.method_params.is_empty() {
+      types += &format!("\n/** Arguments of `{}`. */\nexport interface {}Args {{\n", method.name, name);
+
+      for param in &method.method_params {
+        let field = param_name(&param.name);
+        let ty = Type::parse(&param.param_type)?;
+
+        types += &format!("  {}: {};\n", field, writer.write(&ty));
+        args.push(format!("args.{}", field));
+      }
+
+      types += "}\n";
+    }
+
+    let ty = Type::parse(&method.return_value.value_type)?;
+
+    let returns = match ty {
+      Type::Unit => "void".to_string(),
+      _ => {
+        types += &format!("\n/** Return value of `{}`. */\nexport type {}Return = {};\n", method.name, name, writer.write(&ty));
+        format!("{}Return", name)
+      }
+    };
+
+    let shape = match shape(&ty) {
+      Some(shape) => format!(", {}", shape),
+      None => String::new()
+    };
+
+    let signature = match args.is_empty() {
+      true => String::new(),
+      false => format!("args: {}Args", name)
+    };
+
+    calls += &format!(
+      "  {}: ({}): Call<{}> => call(\"{}\", [{}], {}{}),\n",
+      method.name, signature, returns, method.name, args.join(", "), method.readonly, shape
+    );
+  }
+
+  let mut out = format!("// Generated by `cargo kryolite bindings` from the manifest of {}, do not edit.\n\n", contract.name);
+
+  if !binary.is_empty() {
+    let names = binary.iter().map(|name| format!("`{}`", name)).collect::<Vec<String>>().join(", ");
+    out += &format!("// {} use the binary abi and are left out, encode their calls with kryolite-abi.\n\n", names);
+  }
+
+  out += PRELUDE;
+
+  for name in &writer.named {
+    out += &format!("\n/** Type of the contract, the manifest doesn't describe its fields. */\nexport type {} = unknown;\n", name);
+  }
+
+  out += &types;
+  out += &format!("\n/** Calls of {}. */\nexport const {} = {{\n{}}};\n", contract.name, contract.name, calls);
+
+  Ok(out)
+}
+
+struct Writer {
+  // contract types used so far, declared as `unknown`
+  named: Vec<String>
+}
+
+impl Writer {
+  fn write(&mut self, ty: &Type) -> String {
+    match ty {
+      Type::Unit => "null".into(),
+      Type::Bool => "boolean".into(),
+      // numbers are only exact up to 2^53
+      Type::Int { bits: 64 | 128, .. } => "bigint".into(),
+      Type::Int { .. } | Type::Usize | Type::Isize | Type::Float { .. } => "number".into(),
+      Type::Char | Type::String => "string".into(),
+      Type::Address => "Address".into(),
+      Type::U256 => "U256".into(),
+      Type::Kryo => "Kryo".into(),
+      Type::Option(inner) => format!("{} | null", self.write(inner)),
+      Type::Seq(inner) | Type::Array(inner, _) => match self.write(inner) {
+        inner if inner.contains(" | ") => format!("({})[]", inner),
+        inner => format!("{}[]", inner)
+      },
+      Type::Tuple(types) => format!("[{}]", types.iter().map(|ty| self.write(ty)).collect::<Vec<String>>().join(", ")),
+      // JSON object keys are always strings
+      Type::Map(_, value) => format!("Record<string, {}>", self.write(value)),
+      Type::Named(name) if name.chars().all(|c| c.is_alphanumeric() || c == '_') => {
+        if !self.named.contains(name) {
+          self.named.push(name.clone());
+        }
+
+        name.clone()
+      }
+      // generic contract types have no name to declare
+      Type::Named(_) => "unknown".into()
+    }
+  }
+}
+
+// where `parse` has to make bigints in a value of `ty`, none if there are no bigints in it
+fn shape(ty: &Type) -> Option<String> {
+  match ty {
+    Type::Int { bits: 64 | 128, .. } => Some("\"bigint\"".into()),
+    Type::Option(inner) => shape(inner),
+    Type::Seq(inner) | Type::Array(inner, _) => shape(inner).map(|inner| format!("{{ items: {} }}", inner)),
+    Type::Map(_, value) => shape(value).map(|value| format!("{{ values: {} }}", value)),
+    Type::Tuple(types) => {
+      let shapes: Vec<Option<String>> = types.iter().map(shape).collect();
+
+      match shapes.iter().any(Option::is_some) {
+        true => Some(format!("[{}]", shapes.into_iter().map(|shape| shape.unwrap_or_else(|| "\"value\"".into())).collect::<Vec<String>>().join(", "))),
+        false => None
+      }
+    }
+    _ => None
+  }
+}
+
+fn pascal_case(name: &str) -> String {
+  name.split('_')
+    .filter(|part| !part.is_empty())
+    .map(|part| {
+      let mut chars = part.chars();
+      chars.next().map(|first| first.to_uppercase().chain(chars).collect::<String>()).unwrap_or_default()
+    })
+    .collect()
+}
+
+// parameters are written as their pattern, `mut amount`
+fn param_name(name: &str) -> &str {
+  name.rsplit(' ').next().unwrap_or(name)
+}
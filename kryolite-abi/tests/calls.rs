@@ -0,0 +1,138 @@
+use kryolite_abi::{ContractAbi, Params};
+use serde_json::{json, Value};
+
+// binary contract with a method of a JSON `#[interface]` impl
+const MANIFEST: &str = r#"{
+  "name": "Token",
+  "abi": "binary",
+  "methods": [
+    {
+      "name": "mint",
+      "readonly": false,
+      "abi": "binary",
+      "method_params": [{ "name": "amount", "param_type": "u128" }],
+      "return_value": { "value_type": "u128" }
+    },
+    {
+      "name": "set_limits",
+      "readonly": false,
+      "abi": "binary",
+      "method_params": [{ "name": "limits", "param_type": "OrderedMap<i16, u8>" }],
+      "return_value": { "value_type": "()" }
+    },
+    {
+      "name": "balance_of",
+      "readonly": true,
+      "abi": "json",
+      "method_params": [{ "name": "owner", "param_type": "i128" }],
+      "return_value": { "value_type": "i128" }
+    },
+    {
+      "name": "supplies",
+      "readonly": true,
+      "abi": "json",
+      "method_params": [{ "name": "limit", "param_type": "u64" }, { "name": "offset", "param_type": "u32" }],
+      "return_value": { "value_type": "Vec<u128>" }
+    },
+    {
+      "name": "total_supply",
+      "readonly": true,
+      "abi": "json",
+      "method_params": [],
+      "return_value": { "value_type": "u128" }
+    }
+  ]
+}"#;
+
+fn json_params(abi: &ContractAbi, method: &str, args: Value) -> String {
+  match abi.encode_call(method, &args).unwrap().params {
+    Params::Json(json) => json,
+    Params::Binary(bytes) => panic!("{} was encoded as binary: {:?}", method, bytes)
+  }
+}
+
+#[test]
+fn methods_use_their_own_abi() {
+  let abi = ContractAbi::from_json(MANIFEST).unwrap();
+
+  assert_eq!(abi.encode_call("mint", &json!([5])).unwrap().params, Params::Binary(5u128.to_le_bytes().to_vec()));
+  assert_eq!(abi.decode_return("mint", &7u128.to_le_bytes()).unwrap(), json!("7"));
+
+  assert_eq!(json_params(&abi, "balance_of", json!([5])), "[5]");
+  assert_eq!(abi.decode_return("balance_of", b"-3").unwrap(), json!(-3));
+}
+
+#[test]
+fn writes_128_bit_strings_as_json_numbers() {
+  let abi = ContractAbi::from_json(MANIFEST).unwrap();
+
+  let table = [
+    ("5", "[5]"),
+    ("+5", "[5]"),
+    ("007", "[7]"),
+    ("-007", "[-7]"),
+    ("-0", "[0]"),
+    ("-170141183460469231731687303715884105728", "[-170141183460469231731687303715884105728]")
+  ];
+
+  for (arg, params) in table {
+    let written = json_params(&abi, "balance_of", json!([arg]));
+
+    assert_eq!(written, params, "{}", arg);
+    assert!(serde_json::from_str::<Value>(&written).is_ok(), "{}", written);
+  }
+}
+
+#[test]
+fn only_128_bit_integers_take_strings() {
+  let abi = ContractAbi::from_json(MANIFEST).unwrap();
+
+  let table = [
+    (json!([5, 1]), Some("[5,1]")),
+    (json!(["5", 1]), None),
+    (json!([5, "1"]), None),
+    (json!([-1, 1]), None),
+    (json!([5, 4294967296u64]), None)
+  ];
+
+  for (args, params) in table {
+    match params {
+      Some(params) => assert_eq!(json_params(&abi, "supplies", args), params),
+      None => assert!(abi.encode_call("supplies", &args).is_err(), "{}", args)
+    }
+  }
+
+  assert!(abi.encode_call("balance_of", &json!(["-+5"])).is_err());
+}
+
+#[test]
+fn decodes_128_bit_returns_exactly() {
+  let abi = ContractAbi::from_json(MANIFEST).unwrap();
+
+  let max = u128::MAX.to_string();
+  let total = abi.decode_return("total_supply", max.as_bytes()).unwrap();
+  assert_eq!(total.to_string(), max);
+
+  let supplies = abi.decode_return("supplies", format!("[1,{},170141183460469231731687303715884105729]", max).as_bytes()).unwrap();
+  assert_eq!(supplies.to_string(), format!("[1,{},170141183460469231731687303715884105729]", max));
+
+  let too_large = format!("{}0", max);
+  assert!(abi.decode_return("total_supply", too_large.as_bytes()).is_err());
+  assert!(abi.decode_return("balance_of", b"170141183460469231731687303715884105728").is_err());
+}
+
+#[test]
+fn writes_map_entries_in_key_order() {
+  let abi = ContractAbi::from_json(MANIFEST).unwrap();
+
+  // "-1" < "-2" < "10" < "9" as text
+  let params = abi.encode_call("set_limits", &json!([{ "9": 1, "10": 2, "-2": 3, "-1": 4 }])).unwrap().params;
+
+  let mut expected = 4u32.to_le_bytes().to_vec();
+  for (key, value) in [(-2i16, 3u8), (-1, 4), (9, 1), (10, 2)] {
+    expected.extend_from_slice(&key.to_le_bytes());
+    expected.push(value);
+  }
+
+  assert_eq!(params, Params::Binary(expected));
+}
@@ -0,0 +1,30 @@
+use kryolite_abi::{typescript, AbiError, Abi, Contract};
+
+fn fixture(name: &str) -> Contract {
+  let path = format!("{}/tests/fixtures/{}.json", env!("CARGO_MANIFEST_DIR"), name);
+  Contract::from_json(&std::fs::read_to_string(path).unwrap()).unwrap()
+}
+
+#[test]
+fn lottery() {
+  insta::assert_snapshot!(typescript(&fixture("lottery")).unwrap());
+}
+
+#[test]
+fn types() {
+  insta::assert_snapshot!(typescript(&fixture("types")).unwrap());
+}
+
+// only the json methods of the KRC20 interface get builders
+#[test]
+fn mixed() {
+  insta::assert_snapshot!(typescript(&fixture("mixed")).unwrap());
+}
+
+#[test]
+fn binary_abi() {
+  let mut contract = fixture("mixed");
+  contract.methods.retain(|method| method.abi == Abi::Binary);
+
+  assert_eq!(typescript(&contract), Err(AbiError::BinaryBindings("Token".into())));
+}
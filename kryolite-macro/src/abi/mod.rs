@@ -0,0 +1,50 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote, ToTokens};
+use syn::{parse_quote, FnArg, ImplItemMethod, ReturnType, Type};
+
+// export for #[smart_contract(abi = "binary")] methods: the host passes every argument as
+// one binary encoded tuple and gets the return value back in the same encoding
+pub fn binary_shim(struct_name: &str, method: &ImplItemMethod, param_names: &[String]) -> Result<ImplItemMethod, syn::Error> {
+  let mut receiver: Option<TokenStream> = None;
+  let mut types: Vec<&Type> = Vec::new();
+
+  for arg in &method.sig.inputs {
+    match arg {
+      FnArg::Receiver(rec) => receiver = Some(rec.to_token_stream()),
+      FnArg::Typed(typed) => {
+        if matches!(*typed.ty, Type::Reference(_)) {
+          return Err(syn::Error::new_spanned(&typed.ty, "binary abi methods take their arguments by value"));
+        }
+
+        types.push(&typed.ty);
+      }
+    }
+  }
+
+  let name = &method.sig.ident;
+  let shim_name = format_ident!("{}_abi", name);
+  let names: Vec<TokenStream> = param_names.iter().map(|name| name.parse().unwrap()).collect();
+  let self_ty: Type = syn::parse_str(struct_name)?;
+
+  let receiver = receiver.map(|receiver| quote!(#receiver,));
+
+  let target = match receiver {
+    Some(_) => quote!(self.#name(#(#names),*)),
+    None => quote!(<#self_ty>::#name(#(#names),*))
+  };
+
+  let call = match method.sig.output {
+    ReturnType::Type(..) => quote! {
+      let result = #target;
+      push_return_binary(&result);
+    },
+    ReturnType::Default => quote!(#target;)
+  };
+
+  Ok(parse_quote! {
+    pub fn #shim_name(#receiver __args: *const u8, __args_len: usize) {
+      let (#(#names,)*): (#(#types,)*) = unsafe { decode_args(__args, __args_len) };
+      #call
+    }
+  })
+}
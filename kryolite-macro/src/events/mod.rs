@@ -0,0 +1,34 @@
+use kryolite_manifest::{Contract, Event};
+use syn::{punctuated::Punctuated, Expr, Macro, Token};
+use syn::parse::Parser;
+
+// records event!(Name, args...) invocations so they show up in the manifest
+pub fn collect_event(contract: &mut Contract, mac: &Macro) {
+  if !mac.path.is_ident("event") {
+    return;
+  }
+
+  let args = match Punctuated::<Expr, Token![,]>::parse_terminated.parse2(mac.tokens.clone()) {
+    Ok(args) => args,
+    Err(_) => return,
+  };
+
+  let name = match args.first() {
+    Some(Expr::Path(path)) => match path.path.get_ident() {
+      Some(ident) => ident.to_string(),
+      None => return,
+    },
+    _ => return,
+  };
+
+  if contract.event(&name).is_some() {
+    return;
+  }
+
+  eprintln!("Event: {}", name);
+
+  contract.events.push(Event {
+    name,
+    param_count: args.len() - 1
+  });
+}
@@ -0,0 +1,74 @@
+use syn::{parse_quote, Attribute, Block, FnArg, ImplItemMethod, LitStr, Stmt};
+
+// strips guard attributes (#[only_owner], #[only_role("...")], #[when_paused],
+// #[when_not_paused], #[non_reentrant]) from the method and prepends the matching
+// checks to its body, keeping attribute order.
+// returns the statements that belong in the exported entry point (the generated
+// json shim when there is one, otherwise the method itself)
+pub fn apply_guards(i: &mut ImplItemMethod) -> Vec<Stmt> {
+  let has_receiver = i.sig.inputs.iter().any(|arg| matches!(arg, FnArg::Receiver(_)));
+  let mut checks: Vec<Stmt> = Vec::new();
+  // random stream and other per-call state start fresh on every transaction
+  let mut entry: Vec<Stmt> = vec![parse_quote! {
+    let _call_scope = CallScope::enter();
+  }];
+
+  i.attrs.retain(|attr| {
+    if attr.path.is_ident("non_reentrant") {
+      entry.push(parse_quote! {
+        let _reentrancy_guard = ReentrancyGuard::enter();
+      });
+
+      return false;
+    }
+
+    match guard_check(attr, has_receiver) {
+      Some(Ok(check)) => checks.push(check),
+      Some(Err(err)) => {
+        let err = err.to_compile_error();
+        checks.push(parse_quote! { #err; });
+      }
+      None => return true,
+    }
+
+    false
+  });
+
+  prepend(&mut i.block, checks);
+
+  entry
+}
+
+pub fn prepend(block: &mut Block, stmts: Vec<Stmt>) {
+  block.stmts.splice(0..0, stmts);
+}
+
+fn guard_check(attr: &Attribute, has_receiver: bool) -> Option<Result<Stmt, syn::Error>> {
+  let name = attr.path.get_ident()?.to_string();
+
+  match name.as_str() {
+    "only_owner" => Some(Ok(parse_quote! {
+      require(TRANSACTION.from == CONTRACT.owner);
+    })),
+    "only_role" => Some(attr.parse_args::<LitStr>().and_then(|role| {
+      if !has_receiver {
+        return Err(syn::Error::new_spanned(attr, "#[only_role] requires a method taking self"));
+      }
+
+      Ok(parse_quote! {
+        require(self.access_control.has_role(#role, &TRANSACTION.from));
+      })
+    })),
+    "when_paused" | "when_not_paused" => {
+      if !has_receiver {
+        return Some(Err(syn::Error::new_spanned(attr, format!("#[{}] requires a method taking self", name))));
+      }
+
+      Some(Ok(match name.as_str() {
+        "when_paused" => parse_quote! { require(self.pausable.is_paused()); },
+        _ => parse_quote! { require(!self.pausable.is_paused()); },
+      }))
+    }
+    _ => None,
+  }
+}
@@ -1,3 +1,10 @@
+mod abi;
+mod events;
+mod guards;
+mod lifecycle;
+mod lints;
+mod literals;
+mod options;
 mod write_manifest;
 
 extern crate proc_macro;
@@ -5,32 +12,36 @@ extern crate proc_macro2;
 extern crate quote;
 extern crate syn;
 
-use write_manifest::write_json;
+use abi::binary_shim;
+use events::collect_event;
+use guards::{apply_guards, prepend};
+use kryolite_manifest::{Abi, Contract, Field, Method, Param, ReturnValue, CONTRACT_PART, STATE_PART};
+use lifecycle::{migrate_fn, state_fn, state_version_fn};
+use lints::warn_hash_collections;
+use literals::rewrite_literal;
+use options::parse_options;
+use write_manifest::manifest_static;
+
 use proc_macro2::TokenStream;
 use quote::{ToTokens};
-use serde::Serialize;
 use syn::{
     parse_macro_input, parse_quote,
     visit_mut::{self, VisitMut},
-    Expr, ExprLit, ImplItemMethod, Lit, LitInt, Visibility, FnArg, ReturnType, ImplItem,
+    AttributeArgs, Expr, ImplItemMethod, Item, Visibility, FnArg, ReturnType, ImplItem,
 };
 
 #[proc_macro_attribute]
 pub fn interface(_metadata: proc_macro::TokenStream, input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let mut input = parse_macro_input!(input as syn::File);
-    let walker = &mut TraitWalker { items: Vec::new(), trait_name: "".to_string(), struct_name: "".to_string() };
+    let walker = &mut TraitWalker { items: Vec::new(), trait_name: "".to_string(), struct_name: "".to_string(), contract: Contract::empty() };
 
     eprintln!("file");
 
     syn::visit_mut::visit_file_mut(walker, &mut input);
 
-    let json: String;
-
-    unsafe {
-      json = serde_json::to_string_pretty(&CONTRACT).unwrap();
-    }
+    let part = format!("interface-{}", walker.trait_name);
 
-    write_json(&json);
+    input.items.push(manifest_static(&part, &walker.contract));
 
     // uncomment to see outputs
     // eprintln!("{}", input.to_token_stream().to_string());
@@ -39,21 +50,44 @@ pub fn interface(_metadata: proc_macro::TokenStream, input: proc_macro::TokenStr
 }
 
 #[proc_macro_attribute]
-pub fn smart_contract(_metadata: proc_macro::TokenStream, input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+pub fn smart_contract(metadata: proc_macro::TokenStream, input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let args = parse_macro_input!(metadata as AttributeArgs);
     let mut input = parse_macro_input!(input as syn::File);
-    let walker = &mut StructWalker { items: Vec::new(), struct_name: "".to_string() };
 
-    eprintln!("file");
+    // on the struct only the state is checked, the impl generates the rest
+    if !input.items.iter().any(|item| matches!(item, Item::Impl(_))) {
+      if let Some(arg) = args.first() {
+        return syn::Error::new_spanned(arg, "contract options go on the #[smart_contract] impl").to_compile_error().into();
+      }
 
-    syn::visit_mut::visit_file_mut(walker, &mut input);
+      warn_hash_collections(&mut input);
 
-    let json: String;
+      let state = Contract { state: state_fields(&input), ..Contract::empty() };
+      input.items.push(manifest_static(STATE_PART, &state));
 
-    unsafe {
-      json = serde_json::to_string_pretty(&CONTRACT).unwrap();
+      return input.to_token_stream().into();
     }
 
-    write_json(&json);
+    let options = match parse_options(args) {
+      Ok(options) => options,
+      Err(err) => return err.to_compile_error().into()
+    };
+
+    let contract = Contract {
+      version: options.version,
+      abi: options.abi,
+      // the SDK and the macro are released together
+      sdk_version: Some(env!("CARGO_PKG_VERSION").to_string()),
+      ..Contract::empty()
+    };
+
+    let walker = &mut StructWalker { items: Vec::new(), struct_name: "".to_string(), version: options.version, abi: options.abi, migrate: None, contract };
+
+    eprintln!("file");
+
+    syn::visit_mut::visit_file_mut(walker, &mut input);
+
+    input.items.push(manifest_static(CONTRACT_PART, &walker.contract));
 
     // uncomment to see outputs
     // eprintln!("{}", input.to_token_stream().to_string());
@@ -61,38 +95,43 @@ pub fn smart_contract(_metadata: proc_macro::TokenStream, input: proc_macro::Tok
     input.to_token_stream().into()
 }
 
+// fields of the state struct, written like method params so compat can compare them
+fn state_fields(file: &syn::File) -> Vec<Field> {
+  let fields = file.items.iter().find_map(|item| match item {
+    Item::Struct(state) => Some(&state.fields),
+    _ => None
+  });
+
+  fields.into_iter().flatten().enumerate().map(|(i, field)| Field {
+    name: field.ident.as_ref().map(|ident| ident.to_string()).unwrap_or_else(|| i.to_string()),
+    field_type: field.ty.to_token_stream().to_string().replace(' ', "")
+  }).collect()
+}
+
 struct StructWalker {
   items: Vec<ImplItem>,
-  struct_name: String
+  struct_name: String,
+  version: u32,
+  abi: Abi,
+  migrate: Option<String>,
+  contract: Contract
 }
 
 impl VisitMut for StructWalker {
 
   fn visit_expr_mut(&mut self, i: &mut Expr) {
-    if let Expr::Lit(ExprLit { lit, .. }) = i {
-      match lit {
-        Lit::Int(lit) => {
-          // get literal suffix
-          let suffix = lit.suffix();
-          // get literal without suffix
-          let lit_nosuffix = LitInt::new(lit.base10_digits(), lit.span());
-
-          match suffix {
-            // replace literal expression with new expression
-            "kryo" => *i = parse_quote! { #lit_nosuffix * 1000000 },
-            //"y" => *i = parse_quote! { y_literal(#lit_nosuffix) },
-            _ => (), // other literal suffix we won't modify
-          }
-        }
-
-        _ => (), // other literal type we won't modify
-      }
-    } else {
+    if !rewrite_literal(i) {
       // not a literal, use default visitor method
       visit_mut::visit_expr_mut(self, i)
     }
   }
 
+  fn visit_macro_mut(&mut self, i: &mut syn::Macro) {
+    collect_event(&mut self.contract, i);
+
+    visit_mut::visit_macro_mut(self, i);
+  }
+
   fn visit_item_impl_mut(&mut self, i: &mut syn::ItemImpl) {
     let binding = i.self_ty.to_token_stream().to_string();
     // filter lifetime parameter from name
@@ -101,11 +140,9 @@ impl VisitMut for StructWalker {
 
     self.struct_name = name.to_string();
 
-    unsafe {
-      CONTRACT.name = name.to_string();
-    }
+    self.contract.name = name.to_string();
 
-    let export: TokenStream = format!("#[export_name = \"__init\"]")
+    let export: TokenStream = "#[export_name = \"__init\"]"
     .parse()
     .unwrap();
 
@@ -123,22 +160,45 @@ impl VisitMut for StructWalker {
     };
 
     i.items.push(syn::ImplItem::Method(initfn));
+    i.items.push(syn::ImplItem::Method(state_fn()));
+    i.items.push(syn::ImplItem::Method(state_version_fn(self.version)));
     visit_mut::visit_item_impl_mut(self, i);
 
+    // generated items are final, walking them again would export and list them as methods
     for ele in &self.items {
       i.items.push(ele.clone());
     }
-
-    visit_mut::visit_item_impl_mut(self, i);
   }
 
   fn visit_impl_item_method_mut(&mut self, i: &mut ImplItemMethod) {
+    if let Some(pos) = i.attrs.iter().position(|attr| attr.path.is_ident("migrate")) {
+      i.attrs.remove(pos);
+      self.migrate = Some(i.sig.ident.to_string());
+
+      match migrate_fn(&self.struct_name, i) {
+        Ok(migratefn) => self.items.push(syn::ImplItem::Method(migratefn)),
+        Err(err) => {
+          let err = err.to_compile_error();
+          prepend(&mut i.block, vec![parse_quote! { #err; }]);
+        }
+      }
+    }
+
+    // migration hook is only reachable through __migrate
+    if self.migrate == Some(i.sig.ident.to_string()) {
+      visit_mut::visit_impl_item_method_mut(self, i);
+      return;
+    }
+
+    let entry = apply_guards(i);
+
     match &i.vis {
 
       Visibility::Public(_x) => {
         let name = i.sig.ident.to_string();
 
-        if name == "new" || name == "__init" {
+        if name == "new" || name.starts_with("__") {
+          prepend(&mut i.block, entry);
           visit_mut::visit_impl_item_method_mut(self, i);
           return;
         }
@@ -148,6 +208,7 @@ impl VisitMut for StructWalker {
         let mut method = Method {
           name: name.clone(),
           readonly: false,
+          abi: self.abi,
           method_params: Vec::new(),
           return_value: ReturnValue {
             value_type: "void".to_string()
@@ -187,16 +248,35 @@ impl VisitMut for StructWalker {
 
         let input: TokenStream = i.to_token_stream();
 
-        match &i.sig.output {
-          ReturnType::Type(_arrow, type_arg) => {
-            let type_str = type_arg.to_token_stream().to_string();
-            let value_type = type_str.replace("&", "").replace(" ", "").to_string();
+        if let ReturnType::Type(_arrow, type_arg) = &i.sig.output {
+          let type_str = type_arg.to_token_stream().to_string();
+          let value_type = type_str.replace("&", "").replace(" ", "").to_string();
 
-            eprintln!("Returns: {}", value_type);
+          eprintln!("Returns: {}", value_type);
 
-            method.return_value = ReturnValue {
-              value_type,
-            };
+          method.return_value = ReturnValue {
+            value_type,
+          };
+        }
+
+        match &i.sig.output {
+          // binary abi methods always go through a shim that decodes the arguments
+          _ if self.abi == Abi::Binary => {
+            match binary_shim(&self.struct_name, i, &param_names) {
+              Ok(mut shim) => {
+                let export: TokenStream = format!("#[export_name = \"{}\"]", name).parse().unwrap();
+                shim.attrs.push(parse_quote!(#export));
+
+                prepend(&mut shim.block, entry);
+                self.items.push(syn::ImplItem::Method(shim));
+              }
+              Err(err) => {
+                let err = err.to_compile_error();
+                prepend(&mut i.block, vec![parse_quote! { #err; }]);
+              }
+            }
+          }
+          ReturnType::Type(_arrow, _type_arg) => {
 
             let mut target = format!("self.{}({})", name, param_names.join(", "));
 
@@ -210,15 +290,16 @@ impl VisitMut for StructWalker {
                 let result = {};
                 let json = serde_json::to_string(&result).unwrap();
                 push_return(json.as_str());
-              }}", name, i.sig.inputs.to_token_stream().to_string(), target)
+              }}", name, i.sig.inputs.to_token_stream(), target)
             .parse()
             .unwrap();
 
-            let wrapfn: ImplItemMethod = parse_quote!(
+            let mut wrapfn: ImplItemMethod = parse_quote!(
               #export
               #wrapper
             );
-        
+
+            prepend(&mut wrapfn.block, entry);
             self.items.push(syn::ImplItem::Method(wrapfn));
 
             *i = parse_quote! {
@@ -226,6 +307,8 @@ impl VisitMut for StructWalker {
             };
           }
           _ => {
+            prepend(&mut i.block, entry);
+            let input: TokenStream = i.to_token_stream();
 
             *i = parse_quote! {
                 #export
@@ -234,79 +317,39 @@ impl VisitMut for StructWalker {
           }
         }
 
-        unsafe {
-          CONTRACT.methods.push(method);
-        }
+        self.contract.methods.push(method);
       }
-      _ => (),
+      _ => prepend(&mut i.block, entry),
     }
 
     visit_mut::visit_impl_item_method_mut(self, i);
   }
 }
 
-#[derive(Serialize)]
-struct Contract {
-  pub name: String,
-  pub methods: Vec<Method>
-}
-
-#[derive(Serialize)]
-struct Method {
-  pub name: String,
-  pub readonly: bool,
-  pub method_params: Vec<Param>,
-  pub return_value: ReturnValue
-}
-
-#[derive(Serialize)]
-struct Param {
-  pub name: String,
-  pub param_type: String
-}
-
-#[derive(Serialize)]
-struct ReturnValue {
-  pub value_type: String
-}
-
-static mut CONTRACT: Contract = Contract { name: String::new(), methods: Vec::new()};
-
 // "visitor" that visits every node in the syntax tree
 // we add our own behavior to replace custom literals with proper Rust code
 struct TraitWalker {
   items: Vec<ImplItem>,
   trait_name: String,
-  struct_name: String
+  struct_name: String,
+  contract: Contract
 }
 
 impl VisitMut for TraitWalker {
 
   fn visit_expr_mut(&mut self, i: &mut Expr) {
-    if let Expr::Lit(ExprLit { lit, .. }) = i {
-      match lit {
-        Lit::Int(lit) => {
-          // get literal suffix
-          let suffix = lit.suffix();
-          // get literal without suffix
-          let lit_nosuffix = LitInt::new(lit.base10_digits(), lit.span());
-
-          match suffix {
-            // replace literal expression with new expression
-            "kryo" => *i = parse_quote! { #lit_nosuffix * 1000000 },
-            //"y" => *i = parse_quote! { y_literal(#lit_nosuffix) },
-            _ => (), // other literal suffix we won't modify
-          }
-        }
-
-        _ => (), // other literal type we won't modify
-      }
-    } else {
+    if !rewrite_literal(i) {
       // not a literal, use default visitor method
       visit_mut::visit_expr_mut(self, i)
     }
   }
 
+  fn visit_macro_mut(&mut self, i: &mut syn::Macro) {
+    collect_event(&mut self.contract, i);
+
+    visit_mut::visit_macro_mut(self, i);
+  }
+
   fn visit_item_impl_mut(&mut self, i: &mut syn::ItemImpl) {
     let name = i.self_ty.to_token_stream().to_string();
     // filter lifetime parameter from name
@@ -314,16 +357,20 @@ impl VisitMut for TraitWalker {
     self.struct_name = name.clone();
     self.trait_name = i.trait_.as_ref().unwrap().1.to_token_stream().to_string();
 
+    self.contract.name = name.clone();
+    self.contract.interfaces.push(self.trait_name.clone());
+
     visit_mut::visit_item_impl_mut(self, i);
 
+    // generated items are final, walking them again would export and list them as methods
     for ele in &self.items {
       i.items.push(ele.clone());
     }
-
-    visit_mut::visit_item_impl_mut(self, i);
   }
 
   fn visit_impl_item_method_mut(&mut self, i: &mut ImplItemMethod) {
+    let entry = apply_guards(i);
+
     let name = i.sig.ident.to_string();
 
     eprintln!("Method: {}", name);
@@ -331,6 +378,8 @@ impl VisitMut for TraitWalker {
     let mut method = Method {
       name: name.clone(),
       readonly: false,
+      // the exports are declared by the trait, so interface methods are always JSON
+      abi: Abi::Json,
       method_params: Vec::new(),
       return_value: ReturnValue {
         value_type: "void".to_string()
@@ -393,15 +442,16 @@ impl VisitMut for TraitWalker {
             let result = {};
             let json = serde_json::to_string(&result).unwrap();
             push_return(json.as_str());
-          }}", name, i.sig.inputs.to_token_stream().to_string(), target)
+          }}", name, i.sig.inputs.to_token_stream(), target)
         .parse()
         .unwrap();
 
-        let wrapfn: ImplItemMethod = parse_quote!(
+        let mut wrapfn: ImplItemMethod = parse_quote!(
           #export
           #wrapper
         );
-    
+
+        prepend(&mut wrapfn.block, entry);
         self.items.push(syn::ImplItem::Method(wrapfn));
 
         *i = parse_quote! {
@@ -409,6 +459,8 @@ impl VisitMut for TraitWalker {
         };
       }
       _ => {
+        prepend(&mut i.block, entry);
+        let input: TokenStream = i.to_token_stream();
 
         *i = parse_quote! {
             #export
@@ -417,9 +469,7 @@ impl VisitMut for TraitWalker {
       }
     }
 
-    unsafe {
-      CONTRACT.methods.push(method);
-    }
+    self.contract.methods.push(method);
 
     visit_mut::visit_impl_item_method_mut(self, i);
   }
@@ -0,0 +1,75 @@
+use proc_macro2::TokenStream;
+use syn::{parse_quote, FnArg, ImplItemMethod};
+
+// reports the state layout version so the node can store it with the contract
+// and refuse upgrades that don't move it forward
+pub fn state_version_fn(version: u32) -> ImplItemMethod {
+  let export: TokenStream = "#[export_name = \"__state_version\"]"
+    .parse()
+    .unwrap();
+
+  let function: TokenStream = format!("
+    pub fn __state_version() -> u32 {{
+      {}
+    }}", version)
+    .parse()
+    .unwrap();
+
+  parse_quote! {
+    #export
+    #function
+  }
+}
+
+// returns the whole contract state as json, this is what the node hands to the
+// __migrate export of the new code when the contract gets upgraded
+pub fn state_fn() -> ImplItemMethod {
+  let export: TokenStream = "#[export_name = \"__state\"]"
+    .parse()
+    .unwrap();
+
+  let function: TokenStream = "
+    pub fn __state(&self) {
+      let json = serde_json::to_string(self).unwrap();
+      push_return(json.as_str());
+    }"
+    .parse()
+    .unwrap();
+
+  parse_quote! {
+    #export
+    #function
+  }
+}
+
+// wraps the #[migrate] method, which takes the previous state struct and returns
+// the new one, into an export that works on the json produced by __state
+pub fn migrate_fn(struct_name: &str, method: &ImplItemMethod) -> Result<ImplItemMethod, syn::Error> {
+  let typed_args = method.sig.inputs.iter()
+    .filter(|arg| matches!(arg, FnArg::Typed(_)))
+    .count();
+
+  if typed_args != 1 || method.sig.receiver().is_some() {
+    return Err(syn::Error::new_spanned(&method.sig, "#[migrate] method must take the previous state as its only argument"));
+  }
+
+  let export: TokenStream = "#[export_name = \"__migrate\"]"
+    .parse()
+    .unwrap();
+
+  let function: TokenStream = format!("
+    pub fn __migrate(state: *const u8, state_len: usize) -> *mut u8 {{
+      let _call_scope = CallScope::enter();
+      let json = unsafe {{ core::slice::from_raw_parts(state, state_len) }};
+      let previous = serde_json::from_slice(json).unwrap();
+      let instance = {}::{}(previous);
+      Box::into_raw(Box::new(instance)) as *mut u8
+    }}", struct_name, method.sig.ident)
+    .parse()
+    .unwrap();
+
+  Ok(parse_quote! {
+    #export
+    #function
+  })
+}
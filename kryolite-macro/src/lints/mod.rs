@@ -0,0 +1,49 @@
+use proc_macro2::{TokenStream, TokenTree};
+use quote::{quote, quote_spanned, ToTokens};
+use syn::{parse_quote, File, Item};
+
+// proc macros can't emit warnings on stable, so every use of a hash based collection gets a
+// use of a local deprecated item with the same span, rustc then reports the note right there.
+// Only the field types of the state struct count, locals in methods never reach the state
+pub fn warn_hash_collections(file: &mut File) {
+  let mut uses: Vec<TokenStream> = Vec::new();
+
+  for item in &file.items {
+    if let Item::Struct(state) = item {
+      for field in &state.fields {
+        collect_hash_collections(field.ty.to_token_stream(), &mut uses);
+      }
+    }
+  }
+
+  if uses.is_empty() {
+    return;
+  }
+
+  let warning: Item = parse_quote! {
+    const _: () = {
+      #[deprecated(note = "iteration order of HashMap and HashSet differs between nodes, use OrderedMap, OrderedSet or IndexedMap in contract state")]
+      #[allow(non_camel_case_types)]
+      struct non_deterministic_collection;
+
+      #(#uses)*
+    };
+  };
+
+  file.items.push(warning);
+}
+
+fn collect_hash_collections(tokens: TokenStream, uses: &mut Vec<TokenStream>) {
+  for token in tokens {
+    match token {
+      TokenTree::Group(group) => collect_hash_collections(group.stream(), uses),
+      TokenTree::Ident(ident) if ident == "HashMap" || ident == "HashSet" => {
+        let span = ident.span();
+        let item = quote_spanned!(span=> non_deterministic_collection);
+
+        uses.push(quote! { let _ = #item; });
+      }
+      _ => ()
+    }
+  }
+}
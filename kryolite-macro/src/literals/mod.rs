@@ -0,0 +1,70 @@
+use syn::{parse_quote, Expr, ExprLit, Lit, LitInt};
+
+const KRYO_DECIMALS: i32 = 6;
+
+// replaces custom literals with proper Rust code, returns false when the
+// expression is not a literal so the caller can keep walking into it
+pub fn rewrite_literal(i: &mut Expr) -> bool {
+  let lit = match i {
+    Expr::Lit(ExprLit { lit, .. }) => lit,
+    _ => return false,
+  };
+
+  // digits without underscores and suffix, e.g. `1_000kryo` -> 1000, `0.5kryo` -> 0.5
+  let (digits, span) = match lit {
+    Lit::Int(lit) if lit.suffix() == "kryo" => (lit.base10_digits().to_string(), lit.span()),
+    Lit::Float(lit) if lit.suffix() == "kryo" => (lit.base10_digits().to_string(), lit.span()),
+    _ => return true, // other literal types and suffixes we won't modify
+  };
+
+  *i = match kryo_units(&digits) {
+    Ok(units) => {
+      let units = LitInt::new(&units.to_string(), span);
+      parse_quote! { Kryo::from_units(#units) }
+    }
+    Err(msg) => {
+      let err = syn::Error::new(span, msg).to_compile_error();
+      parse_quote! { #err }
+    }
+  };
+
+  true
+}
+
+// exact conversion of a decimal literal to millionths of a KRYO
+fn kryo_units(digits: &str) -> Result<u64, String> {
+  let (mantissa, exponent) = match digits.find(['e', 'E']) {
+    Some(pos) => {
+      let exponent: i32 = digits[pos + 1..].parse().map_err(|_| format!("invalid exponent in {}kryo", digits))?;
+      (&digits[..pos], exponent)
+    }
+    None => (digits, 0),
+  };
+
+  let (whole, fraction) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+  let mut significant = format!("{}{}", whole, fraction).trim_start_matches('0').to_string();
+  let mut scale = exponent + KRYO_DECIMALS - fraction.len() as i32;
+
+  // digits below the sixth decimal must all be zero
+  while scale < 0 && !significant.is_empty() {
+    if significant.pop() != Some('0') {
+      return Err(format!("{}kryo is below the precision of 0.000001 kryo", digits));
+    }
+
+    scale += 1;
+  }
+
+  let too_large = || format!("{}kryo does not fit in u64", digits);
+
+  if significant.is_empty() {
+    return Ok(0);
+  }
+
+  let mut units: u128 = significant.parse().map_err(|_| too_large())?;
+
+  for _ in 0..scale {
+    units = units.checked_mul(10).ok_or_else(too_large)?;
+  }
+
+  u64::try_from(units).map_err(|_| too_large())
+}
@@ -0,0 +1,42 @@
+use kryolite_manifest::Abi;
+use syn::{AttributeArgs, Lit, Meta, NestedMeta};
+
+// arguments given to #[smart_contract(...)]
+pub struct ContractOptions {
+  pub version: u32,
+  pub abi: Abi
+}
+
+impl Default for ContractOptions {
+  fn default() -> Self {
+    ContractOptions {
+      version: 1,
+      abi: Abi::Json
+    }
+  }
+}
+
+pub fn parse_options(args: AttributeArgs) -> Result<ContractOptions, syn::Error> {
+  let mut options = ContractOptions::default();
+
+  for arg in args {
+    match arg {
+      NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("version") => {
+        match &nv.lit {
+          Lit::Int(lit) => options.version = lit.base10_parse()?,
+          lit => return Err(syn::Error::new_spanned(lit, "expected integer state version")),
+        }
+      }
+      NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("abi") => {
+        options.abi = match &nv.lit {
+          Lit::Str(lit) if lit.value() == "json" => Abi::Json,
+          Lit::Str(lit) if lit.value() == "binary" => Abi::Binary,
+          lit => return Err(syn::Error::new_spanned(lit, "expected abi = \"json\" or abi = \"binary\"")),
+        }
+      }
+      other => return Err(syn::Error::new_spanned(other, "unknown smart_contract argument")),
+    }
+  }
+
+  Ok(options)
+}
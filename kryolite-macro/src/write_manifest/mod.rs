@@ -1,7 +1,25 @@
-use std::io::Write;
+use kryolite_manifest::{encode_fragment, Contract, MANIFEST_SECTION};
+use proc_macro2::Literal;
+use quote::format_ident;
+use syn::{parse_quote, Item};
 
-pub fn write_json(json: &String) {
-  let _ = std::fs::create_dir("pkg").is_ok();
-  let mut file = std::fs::File::create("pkg/manifest.json").expect("create failed");
-  file.write_all(json.as_bytes()).expect("write failed");
+// every #[smart_contract] and #[interface] impl only sees itself, so each one puts its part
+// of the manifest into the kryolite_manifest section and Contract::from_wasm merges them
+pub fn manifest_static(part: &str, contract: &Contract) -> Item {
+  let name = format_ident!("__KRYOLITE_MANIFEST_{}", file_name(part).replace('-', "_").to_uppercase());
+  let fragment = encode_fragment(part, contract);
+  let len = fragment.len();
+  let bytes = Literal::byte_string(&fragment);
+
+  parse_quote! {
+    #[cfg(target_arch = "wasm32")]
+    #[link_section = #MANIFEST_SECTION]
+    #[used]
+    static #name: [u8; #len] = *#bytes;
+  }
+}
+
+// trait paths like `krc::KRC721<T>` as part of an identifier
+fn file_name(part: &str) -> String {
+  part.chars().map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' }).collect()
 }
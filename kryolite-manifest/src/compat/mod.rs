@@ -0,0 +1,255 @@
+use alloc::{format, string::String, vec::Vec};
+use core::fmt;
+
+use super::{Abi, Contract, Event, Field, Method};
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Severity {
+  Compatible,
+  Breaking
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum Change {
+  ContractRenamed { from: String, to: String },
+  StateVersionChanged { from: u32, to: u32 },
+  /// Fields as `name: type`, `migrated` when the state version went up with them.
+  StateFieldsChanged { from: Vec<String>, to: Vec<String>, migrated: bool },
+  AbiChanged { from: Abi, to: Abi },
+  MethodAdded(String),
+  MethodRemoved(String),
+  MethodRenamed { from: String, to: String },
+  MethodAbiChanged { method: String, from: Abi, to: Abi },
+  ParamTypesChanged { method: String, from: Vec<String>, to: Vec<String> },
+  ParamNamesChanged { method: String, from: Vec<String>, to: Vec<String> },
+  ReturnTypeChanged { method: String, from: String, to: String },
+  ReadonlyChanged { method: String, readonly: bool },
+  EventAdded(String),
+  EventRemoved(String),
+  EventParamsChanged { event: String, from: usize, to: usize },
+  InterfaceAdded(String),
+  InterfaceRemoved(String)
+}
+
+impl Change {
+  pub fn severity(&self) -> Severity {
+    match self {
+      Change::ContractRenamed { .. } => Severity::Compatible,
+      Change::StateVersionChanged { from, to } if to > from => Severity::Compatible,
+      Change::StateVersionChanged { .. } => Severity::Breaking,
+      // the stored state can't be read as the new struct unless #[migrate] converts it
+      Change::StateFieldsChanged { migrated, .. } if *migrated => Severity::Compatible,
+      Change::StateFieldsChanged { .. } => Severity::Breaking,
+      Change::AbiChanged { .. } => Severity::Breaking,
+      Change::MethodAdded(_) => Severity::Compatible,
+      Change::MethodRemoved(_) => Severity::Breaking,
+      Change::MethodRenamed { .. } => Severity::Breaking,
+      Change::MethodAbiChanged { .. } => Severity::Breaking,
+      Change::ParamTypesChanged { .. } => Severity::Breaking,
+      // the contract reads arguments by position, but clients may pass them by name
+      Change::ParamNamesChanged { .. } => Severity::Breaking,
+      Change::ReturnTypeChanged { .. } => Severity::Breaking,
+      // callers querying a readonly method can't call it anymore once it writes state
+      Change::ReadonlyChanged { readonly, .. } if *readonly => Severity::Compatible,
+      Change::ReadonlyChanged { .. } => Severity::Breaking,
+      Change::EventAdded(_) => Severity::Compatible,
+      Change::EventRemoved(_) => Severity::Breaking,
+      Change::EventParamsChanged { .. } => Severity::Breaking,
+      Change::InterfaceAdded(_) => Severity::Compatible,
+      Change::InterfaceRemoved(_) => Severity::Breaking
+    }
+  }
+
+  pub fn is_breaking(&self) -> bool {
+    self.severity() == Severity::Breaking
+  }
+}
+
+impl fmt::Display for Change {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let severity = match self.severity() {
+      Severity::Compatible => "compatible",
+      Severity::Breaking => "breaking"
+    };
+
+    write!(f, "[{}] ", severity)?;
+
+    match self {
+      Change::ContractRenamed { from, to } => write!(f, "contract renamed from `{}` to `{}`", from, to),
+      Change::StateVersionChanged { from, to } if to > from => write!(f, "state version {} -> {}, state is migrated with #[migrate]", from, to),
+      Change::StateVersionChanged { from, to } => write!(f, "state version went backwards {} -> {}", from, to),
+      Change::StateFieldsChanged { from, to, migrated: true } => write!(f, "state fields changed from ({}) to ({}) with a new state version", from.join(", "), to.join(", ")),
+      Change::StateFieldsChanged { from, to, migrated: false } => write!(f, "state fields changed from ({}) to ({}) without a new state version", from.join(", "), to.join(", ")),
+      Change::AbiChanged { from, to } => write!(f, "abi changed from {} to {}", from.as_str(), to.as_str()),
+      Change::MethodAdded(name) => write!(f, "method `{}` added", name),
+      Change::MethodRemoved(name) => write!(f, "method `{}` removed", name),
+      Change::MethodRenamed { from, to } => write!(f, "method `{}` renamed to `{}`", from, to),
+      Change::MethodAbiChanged { method, from, to } => write!(f, "method `{}` abi changed from {} to {}", method, from.as_str(), to.as_str()),
+      Change::ParamTypesChanged { method, from, to } => write!(f, "method `{}` parameters changed from ({}) to ({})", method, from.join(", "), to.join(", ")),
+      Change::ParamNamesChanged { method, from, to } => write!(f, "method `{}` parameters renamed from ({}) to ({})", method, from.join(", "), to.join(", ")),
+      Change::ReturnTypeChanged { method, from, to } => write!(f, "method `{}` return type changed from {} to {}", method, from, to),
+      Change::ReadonlyChanged { method, readonly: true } => write!(f, "method `{}` is now readonly", method),
+      Change::ReadonlyChanged { method, readonly: false } => write!(f, "method `{}` is no longer readonly", method),
+      Change::EventAdded(name) => write!(f, "event `{}` added", name),
+      Change::EventRemoved(name) => write!(f, "event `{}` removed", name),
+      Change::EventParamsChanged { event, from, to } => write!(f, "event `{}` now has {} parameters instead of {}", event, to, from),
+      Change::InterfaceAdded(name) => write!(f, "interface `{}` added", name),
+      Change::InterfaceRemoved(name) => write!(f, "interface `{}` removed", name)
+    }
+  }
+}
+
+/// Lists every difference between an old and a new manifest that matters to callers or storage.
+/// State fields are only compared when both manifests have them, older manifests only record the
+/// state version.
+pub fn compare(old: &Contract, new: &Contract) -> Vec<Change> {
+  let mut changes: Vec<Change> = Vec::new();
+
+  if old.name != new.name {
+    changes.push(Change::ContractRenamed { from: old.name.clone(), to: new.name.clone() });
+  }
+
+  if old.version != new.version {
+    changes.push(Change::StateVersionChanged { from: old.version, to: new.version });
+  }
+
+  if !old.state.is_empty() && !new.state.is_empty() && old.state != new.state {
+    changes.push(Change::StateFieldsChanged { from: fields(&old.state), to: fields(&new.state), migrated: new.version > old.version });
+  }
+
+  if old.abi != new.abi {
+    changes.push(Change::AbiChanged { from: old.abi, to: new.abi });
+  }
+
+  // a contract wide switch already covers the methods of the impl
+  compare_methods(&unique_methods(old), &unique_methods(new), old.abi == new.abi, &mut changes);
+  compare_events(&old.events, &new.events, &mut changes);
+
+  for interface in &old.interfaces {
+    if !new.interfaces.contains(interface) {
+      changes.push(Change::InterfaceRemoved(interface.clone()));
+    }
+  }
+
+  for interface in &new.interfaces {
+    if !old.interfaces.contains(interface) {
+      changes.push(Change::InterfaceAdded(interface.clone()));
+    }
+  }
+
+  changes
+}
+
+fn fields(state: &[Field]) -> Vec<String> {
+  state.iter().map(|field| format!("{}: {}", field.name, field.field_type)).collect()
+}
+
+pub fn is_compatible(changes: &[Change]) -> bool {
+  !changes.iter().any(|change| change.is_breaking())
+}
+
+fn compare_methods(old: &[&Method], new: &[&Method], same_abi: bool, changes: &mut Vec<Change>) {
+  let mut removed: Vec<&Method> = Vec::new();
+  let mut added: Vec<&Method> = new.iter()
+    .filter(|method| !old.iter().any(|x| x.name == method.name))
+    .copied()
+    .collect();
+
+  for old_method in old {
+    let new_method = match new.iter().find(|x| x.name == old_method.name) {
+      Some(method) => method,
+      None => {
+        removed.push(old_method);
+        continue;
+      }
+    };
+
+    let name = old_method.name.clone();
+    let old_types = param_types(old_method);
+    let new_types = param_types(new_method);
+
+    if old_types != new_types {
+      changes.push(Change::ParamTypesChanged { method: name.clone(), from: old_types, to: new_types });
+    } else if param_names(old_method) != param_names(new_method) {
+      changes.push(Change::ParamNamesChanged { method: name.clone(), from: param_names(old_method), to: param_names(new_method) });
+    }
+
+    if old_method.return_value != new_method.return_value {
+      changes.push(Change::ReturnTypeChanged {
+        method: name.clone(),
+        from: old_method.return_value.value_type.clone(),
+        to: new_method.return_value.value_type.clone()
+      });
+    }
+
+    if same_abi && old_method.abi != new_method.abi {
+      changes.push(Change::MethodAbiChanged { method: name.clone(), from: old_method.abi, to: new_method.abi });
+    }
+
+    if old_method.readonly != new_method.readonly {
+      changes.push(Change::ReadonlyChanged { method: name, readonly: new_method.readonly });
+    }
+  }
+
+  // a removed method with an added twin of the exact same signature is most likely a rename
+  for old_method in removed {
+    match added.iter().position(|x| same_signature(old_method, x)) {
+      Some(pos) => {
+        let new_method = added.remove(pos);
+        changes.push(Change::MethodRenamed { from: old_method.name.clone(), to: new_method.name.clone() });
+      }
+      None => changes.push(Change::MethodRemoved(old_method.name.clone()))
+    }
+  }
+
+  for new_method in added {
+    changes.push(Change::MethodAdded(new_method.name.clone()));
+  }
+}
+
+fn compare_events(old: &[Event], new: &[Event], changes: &mut Vec<Change>) {
+  for old_event in old {
+    match new.iter().find(|x| x.name == old_event.name) {
+      Some(new_event) if new_event.param_count != old_event.param_count => {
+        changes.push(Change::EventParamsChanged {
+          event: old_event.name.clone(),
+          from: old_event.param_count,
+          to: new_event.param_count
+        });
+      }
+      Some(_) => (),
+      None => changes.push(Change::EventRemoved(old_event.name.clone()))
+    }
+  }
+
+  for new_event in new {
+    if !old.iter().any(|x| x.name == new_event.name) {
+      changes.push(Change::EventAdded(new_event.name.clone()));
+    }
+  }
+}
+
+// manifests written by older macro versions may list the same method more than once
+fn unique_methods(contract: &Contract) -> Vec<&Method> {
+  let mut methods: Vec<&Method> = Vec::new();
+
+  for method in &contract.methods {
+    if !methods.iter().any(|x| x.name == method.name) {
+      methods.push(method);
+    }
+  }
+
+  methods
+}
+
+fn same_signature(a: &Method, b: &Method) -> bool {
+  a.readonly == b.readonly && a.abi == b.abi && a.return_value == b.return_value && param_types(a) == param_types(b)
+}
+
+fn param_types(method: &Method) -> Vec<String> {
+  method.method_params.iter().map(|param| param.param_type.clone()).collect()
+}
+
+fn param_names(method: &Method) -> Vec<String> {
+  method.method_params.iter().map(|param| param.name.clone()).collect()
+}
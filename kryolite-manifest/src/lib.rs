@@ -0,0 +1,162 @@
+// no_std so contracts and the SDK can read manifests too
+#![no_std]
+
+extern crate alloc;
+
+mod compat;
+mod validate;
+mod wasm;
+
+pub use compat::*;
+pub use validate::*;
+pub use wasm::*;
+
+use alloc::{string::String, vec::Vec};
+use serde::{Serialize, Deserialize};
+
+/// Manifest of a contract, embedded in its code by `#[smart_contract]` and `#[interface]` and
+/// copied to `pkg/manifest.json` by `cargo kryolite build`.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct Contract {
+  pub name: String,
+  #[serde(default = "default_version")]
+  pub version: u32,
+  /// Abi of the `#[smart_contract]` impl, every method records the one it uses.
+  #[serde(default)]
+  pub abi: Abi,
+  pub methods: Vec<Method>,
+  #[serde(default)]
+  pub events: Vec<Event>,
+  #[serde(default)]
+  pub interfaces: Vec<String>,
+  /// Fields of the state struct in declaration order, recorded by `#[smart_contract]` on the
+  /// struct. Empty in manifests of contracts without it.
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub state: Vec<Field>,
+  /// Version of the SDK the contract was built with.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub sdk_version: Option<String>
+}
+
+/// Encoding of method arguments and return values, chosen with `#[smart_contract(abi = "...")]`.
+#[derive(Serialize, Deserialize, Copy, Clone, PartialEq, Eq, Default, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum Abi {
+  /// Arguments are passed as wasm parameters, return values are JSON.
+  #[default]
+  Json,
+  /// Arguments and return values are in the compact binary encoding of `kryolite_smart_contract::abi_encode`,
+  /// the arguments as a tuple passed by pointer and length. Only covers the `#[smart_contract]`
+  /// impl, `#[interface]` methods stay JSON since their exports are declared by the trait and
+  /// are listed with `Abi::Json`.
+  Binary
+}
+
+impl Abi {
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      Abi::Json => "json",
+      Abi::Binary => "binary"
+    }
+  }
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct Method {
+  pub name: String,
+  pub readonly: bool,
+  #[serde(default)]
+  pub abi: Abi,
+  pub method_params: Vec<Param>,
+  pub return_value: ReturnValue
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct Param {
+  pub name: String,
+  pub param_type: String
+}
+
+/// Field of the state struct, `name` is the index for tuple structs.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct Field {
+  pub name: String,
+  pub field_type: String
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct ReturnValue {
+  pub value_type: String
+}
+
+/// Event published with `event!(Name, ...)` somewhere in the contract.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct Event {
+  pub name: String,
+  pub param_count: usize
+}
+
+impl Contract {
+  pub const fn empty() -> Contract {
+    Contract {
+      name: String::new(),
+      version: 1,
+      abi: Abi::Json,
+      methods: Vec::new(),
+      events: Vec::new(),
+      interfaces: Vec::new(),
+      state: Vec::new(),
+      sdk_version: None
+    }
+  }
+
+  pub fn from_json(json: &str) -> Result<Contract, serde_json::Error> {
+    serde_json::from_str(json)
+  }
+
+  pub fn to_json(&self) -> String {
+    serde_json::to_string_pretty(self).unwrap()
+  }
+
+  pub fn method(&self, name: &str) -> Option<&Method> {
+    self.methods.iter().find(|method| method.name == name)
+  }
+
+  pub fn event(&self, name: &str) -> Option<&Event> {
+    self.events.iter().find(|event| event.name == name)
+  }
+
+  /// Adds the methods, events, interfaces and state of another part of the same contract, e.g.
+  /// an `#[interface]` impl. Anything listed already is kept as is.
+  pub fn merge(&mut self, other: &Contract) {
+    for method in &other.methods {
+      if self.method(&method.name).is_none() {
+        self.methods.push(method.clone());
+      }
+    }
+
+    for event in &other.events {
+      if self.event(&event.name).is_none() {
+        self.events.push(event.clone());
+      }
+    }
+
+    for interface in &other.interfaces {
+      if !self.interfaces.contains(interface) {
+        self.interfaces.push(interface.clone());
+      }
+    }
+
+    if self.state.is_empty() {
+      self.state = other.state.clone();
+    }
+
+    if self.sdk_version.is_none() {
+      self.sdk_version = other.sdk_version.clone();
+    }
+  }
+}
+
+fn default_version() -> u32 {
+  1
+}
@@ -0,0 +1,48 @@
+use std::process::exit;
+
+use kryolite_manifest::{compare, is_compatible, Contract};
+
+const USAGE: &str = "usage: kryolite-manifest diff <old manifest.json> <new manifest.json>";
+
+fn main() {
+  let args: Vec<String> = std::env::args().skip(1).collect();
+
+  match args.iter().map(|arg| arg.as_str()).collect::<Vec<&str>>().as_slice() {
+    ["diff", old, new] => diff(old, new),
+    _ => {
+      eprintln!("{}", USAGE);
+      exit(2);
+    }
+  }
+}
+
+// exits with 1 when the new manifest breaks callers or storage
+fn diff(old_path: &str, new_path: &str) {
+  let old = load(old_path);
+  let new = load(new_path);
+
+  let changes = compare(&old, &new);
+
+  for change in &changes {
+    println!("{}", change);
+  }
+
+  if !is_compatible(&changes) {
+    println!("{} is not compatible with {}", new_path, old_path);
+    exit(1);
+  }
+
+  println!("{} is compatible with {}", new_path, old_path);
+}
+
+fn load(path: &str) -> Contract {
+  let json = std::fs::read_to_string(path).unwrap_or_else(|err| {
+    eprintln!("failed to read {}: {}", path, err);
+    exit(2);
+  });
+
+  Contract::from_json(&json).unwrap_or_else(|err| {
+    eprintln!("failed to parse {}: {}", path, err);
+    exit(2);
+  })
+}
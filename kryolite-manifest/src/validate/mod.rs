@@ -0,0 +1,88 @@
+use alloc::{string::String, vec::Vec};
+use core::fmt;
+
+use super::Contract;
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum Problem {
+  MissingName,
+  InvalidMethodName(String),
+  /// Listed twice with different signatures, callers can't tell which one the export is.
+  ConflictingMethod(String),
+  DuplicateParam { method: String, param: String },
+  InvalidEventName(String),
+  ConflictingEvent(String)
+}
+
+impl fmt::Display for Problem {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Problem::MissingName => write!(f, "contract has no name"),
+      Problem::InvalidMethodName(name) => write!(f, "method `{}` can't be exported under that name", name),
+      Problem::ConflictingMethod(name) => write!(f, "method `{}` is listed with different signatures", name),
+      Problem::DuplicateParam { method, param } => write!(f, "method `{}` has parameter `{}` more than once", method, param),
+      Problem::InvalidEventName(name) => write!(f, "event `{}` is not a valid name", name),
+      Problem::ConflictingEvent(name) => write!(f, "event `{}` is listed with different parameter counts", name)
+    }
+  }
+}
+
+/// Checks a manifest for anything the node or a client would trip over. Methods listed twice
+/// with the same signature are fine, every export still has one meaning.
+pub fn validate(contract: &Contract) -> Vec<Problem> {
+  let mut problems: Vec<Problem> = Vec::new();
+
+  if contract.name.is_empty() {
+    problems.push(Problem::MissingName);
+  }
+
+  for (i, method) in contract.methods.iter().enumerate() {
+    let earlier = &contract.methods[..i];
+
+    if earlier.iter().any(|x| x.name == method.name) {
+      if earlier.iter().any(|x| x.name == method.name && x != method) && !problems.contains(&Problem::ConflictingMethod(method.name.clone())) {
+        problems.push(Problem::ConflictingMethod(method.name.clone()));
+      }
+
+      continue;
+    }
+
+    // `new` and `__` exports belong to the contract lifecycle
+    if !is_identifier(&method.name) || method.name == "new" || method.name.starts_with("__") {
+      problems.push(Problem::InvalidMethodName(method.name.clone()));
+    }
+
+    for (j, param) in method.method_params.iter().enumerate() {
+      if method.method_params[..j].iter().any(|x| x.name == param.name) {
+        problems.push(Problem::DuplicateParam { method: method.name.clone(), param: param.name.clone() });
+      }
+    }
+  }
+
+  for (i, event) in contract.events.iter().enumerate() {
+    let earlier = &contract.events[..i];
+
+    if earlier.iter().any(|x| x.name == event.name) {
+      if earlier.iter().any(|x| x.name == event.name && x.param_count != event.param_count) && !problems.contains(&Problem::ConflictingEvent(event.name.clone())) {
+        problems.push(Problem::ConflictingEvent(event.name.clone()));
+      }
+
+      continue;
+    }
+
+    if !is_identifier(&event.name) {
+      problems.push(Problem::InvalidEventName(event.name.clone()));
+    }
+  }
+
+  problems
+}
+
+fn is_identifier(name: &str) -> bool {
+  let mut chars = name.chars();
+
+  match chars.next() {
+    Some(first) if first.is_ascii_alphabetic() || first == '_' => chars.all(|c| c.is_ascii_alphanumeric() || c == '_'),
+    _ => false
+  }
+}
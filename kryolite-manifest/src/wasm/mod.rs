@@ -0,0 +1,135 @@
+use alloc::{string::{String, ToString}, vec::Vec};
+use core::fmt;
+
+use serde::{Serialize, Deserialize};
+
+use super::Contract;
+
+/// Custom section of the contract code that holds its manifest.
+pub const MANIFEST_SECTION: &str = "kryolite_manifest";
+
+/// Part written by `#[smart_contract]`, every `#[interface]` impl adds one of its own.
+pub const CONTRACT_PART: &str = "contract";
+/// Part written by `#[smart_contract]` on the state struct, only its `state` is used.
+pub const STATE_PART: &str = "state";
+
+// the linker concatenates custom sections with the same name, so every macro invocation adds
+// its own length prefixed fragment and the reader merges them
+#[derive(Serialize, Deserialize)]
+struct Fragment {
+  part: String,
+  contract: Contract
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum WasmError {
+  NotWasm,
+  Truncated,
+  MissingManifest,
+  InvalidFragment(String)
+}
+
+impl fmt::Display for WasmError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      WasmError::NotWasm => write!(f, "not a wasm module"),
+      WasmError::Truncated => write!(f, "wasm module is truncated"),
+      WasmError::MissingManifest => write!(f, "no {} section, the contract was built without a manifest", MANIFEST_SECTION),
+      WasmError::InvalidFragment(err) => write!(f, "invalid manifest in {} section: {}", MANIFEST_SECTION, err)
+    }
+  }
+}
+
+/// Encodes one part of the manifest for the `kryolite_manifest` section.
+pub fn encode_fragment(part: &str, contract: &Contract) -> Vec<u8> {
+  let json = serde_json::to_vec(&Fragment { part: part.into(), contract: contract.clone() }).unwrap();
+
+  let mut bytes = (json.len() as u32).to_le_bytes().to_vec();
+  bytes.extend_from_slice(&json);
+  bytes
+}
+
+/// Combines the parts of a manifest, the result doesn't depend on the order they come in:
+/// the contract part first, then the other parts sorted by name.
+pub fn merge_parts(mut parts: Vec<(String, Contract)>) -> Contract {
+  parts.sort_by(|a, b| (a.0 != CONTRACT_PART, &a.0).cmp(&(b.0 != CONTRACT_PART, &b.0)));
+
+  let mut parts = parts.into_iter().map(|(_, contract)| contract);
+  let mut manifest = parts.next().unwrap_or_else(Contract::empty);
+
+  for part in parts {
+    manifest.merge(&part);
+  }
+
+  manifest.interfaces.sort();
+  manifest
+}
+
+impl Contract {
+  /// Reads the manifest embedded in contract code.
+  pub fn from_wasm(wasm: &[u8]) -> Result<Contract, WasmError> {
+    let mut parts: Vec<(String, Contract)> = Vec::new();
+
+    for mut section in custom_sections(wasm, MANIFEST_SECTION)? {
+      while !section.is_empty() {
+        let len = section.get(..4).ok_or(WasmError::Truncated)?;
+        let len = u32::from_le_bytes(len.try_into().unwrap()) as usize;
+        let json = section.get(4..4 + len).ok_or(WasmError::Truncated)?;
+
+        let fragment: Fragment = serde_json::from_slice(json).map_err(|err| WasmError::InvalidFragment(err.to_string()))?;
+        parts.push((fragment.part, fragment.contract));
+
+        section = &section[4 + len..];
+      }
+    }
+
+    match parts.is_empty() {
+      true => Err(WasmError::MissingManifest),
+      false => Ok(merge_parts(parts))
+    }
+  }
+}
+
+// contents of every custom section called `name`
+fn custom_sections<'a>(wasm: &'a [u8], name: &str) -> Result<Vec<&'a [u8]>, WasmError> {
+  if wasm.get(..4) != Some(b"\0asm".as_slice()) || wasm.len() < 8 {
+    return Err(WasmError::NotWasm);
+  }
+
+  let mut sections: Vec<&[u8]> = Vec::new();
+  let mut rest = &wasm[8..];
+
+  while let Some((&id, after_id)) = rest.split_first() {
+    let (size, after_size) = read_leb128(after_id)?;
+    let contents = after_size.get(..size).ok_or(WasmError::Truncated)?;
+
+    // custom sections start with their name
+    if id == 0 {
+      let (name_len, after_len) = read_leb128(contents)?;
+      let section_name = after_len.get(..name_len).ok_or(WasmError::Truncated)?;
+
+      if section_name == name.as_bytes() {
+        sections.push(&after_len[name_len..]);
+      }
+    }
+
+    rest = &after_size[size..];
+  }
+
+  Ok(sections)
+}
+
+// unsigned LEB128 as used for sizes, returns the value and the bytes after it
+fn read_leb128(bytes: &[u8]) -> Result<(usize, &[u8]), WasmError> {
+  let mut value: usize = 0;
+
+  for (i, byte) in bytes.iter().enumerate().take(5) {
+    value |= ((byte & 0x7f) as usize) << (7 * i);
+
+    if byte & 0x80 == 0 {
+      return Ok((value, &bytes[i + 1..]));
+    }
+  }
+
+  Err(WasmError::Truncated)
+}
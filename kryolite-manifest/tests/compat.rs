@@ -0,0 +1,147 @@
+use kryolite_manifest::{compare, is_compatible, merge_parts, Abi, Change, Contract, Event, Field, Method, Param, ReturnValue, Severity};
+
+// change made to the new manifest
+type Edit = fn(&mut Contract);
+
+fn method(name: &str, params: &[(&str, &str)], returns: &str, readonly: bool) -> Method {
+  Method {
+    name: name.into(),
+    readonly,
+    abi: Abi::Json,
+    method_params: params.iter().map(|(name, ty)| Param { name: (*name).into(), param_type: (*ty).into() }).collect(),
+    return_value: ReturnValue { value_type: returns.into() }
+  }
+}
+
+fn field(name: &str, ty: &str) -> Field {
+  Field { name: name.into(), field_type: ty.into() }
+}
+
+fn contract() -> Contract {
+  Contract {
+    name: "Token".into(),
+    version: 1,
+    abi: Abi::Json,
+    methods: vec![
+      method("transfer", &[("to", "Address"), ("amount", "u64")], "void", false),
+      method("balance_of", &[("owner", "Address")], "u64", true)
+    ],
+    events: vec![Event { name: "Transfer".into(), param_count: 3 }],
+    interfaces: vec!["KRC20".into()],
+    state: vec![field("balances", "OrderedMap<Address,u64>"), field("supply", "u64")],
+    sdk_version: None
+  }
+}
+
+fn strings(items: &[&str]) -> Vec<String> {
+  items.iter().map(|item| (*item).into()).collect()
+}
+
+#[test]
+fn severity() {
+  let table = [
+    (Change::ContractRenamed { from: "A".into(), to: "B".into() }, Severity::Compatible),
+    (Change::StateVersionChanged { from: 1, to: 2 }, Severity::Compatible),
+    (Change::StateVersionChanged { from: 2, to: 1 }, Severity::Breaking),
+    (Change::StateFieldsChanged { from: strings(&["a: u64"]), to: strings(&["a: u32"]), migrated: true }, Severity::Compatible),
+    (Change::StateFieldsChanged { from: strings(&["a: u64"]), to: strings(&["a: u32"]), migrated: false }, Severity::Breaking),
+    (Change::AbiChanged { from: Abi::Json, to: Abi::Binary }, Severity::Breaking),
+    (Change::MethodAdded("mint".into()), Severity::Compatible),
+    (Change::MethodRemoved("mint".into()), Severity::Breaking),
+    (Change::MethodRenamed { from: "mint".into(), to: "issue".into() }, Severity::Breaking),
+    (Change::MethodAbiChanged { method: "mint".into(), from: Abi::Json, to: Abi::Binary }, Severity::Breaking),
+    (Change::ParamTypesChanged { method: "mint".into(), from: strings(&["u64"]), to: strings(&["u32"]) }, Severity::Breaking),
+    (Change::ParamNamesChanged { method: "mint".into(), from: strings(&["amount"]), to: strings(&["value"]) }, Severity::Breaking),
+    (Change::ReturnTypeChanged { method: "mint".into(), from: "void".into(), to: "bool".into() }, Severity::Breaking),
+    (Change::ReadonlyChanged { method: "mint".into(), readonly: true }, Severity::Compatible),
+    (Change::ReadonlyChanged { method: "mint".into(), readonly: false }, Severity::Breaking),
+    (Change::EventAdded("Mint".into()), Severity::Compatible),
+    (Change::EventRemoved("Mint".into()), Severity::Breaking),
+    (Change::EventParamsChanged { event: "Mint".into(), from: 1, to: 2 }, Severity::Breaking),
+    (Change::InterfaceAdded("KRC721".into()), Severity::Compatible),
+    (Change::InterfaceRemoved("KRC721".into()), Severity::Breaking)
+  ];
+
+  for (change, severity) in table {
+    assert_eq!(change.severity(), severity, "{}", change);
+    assert_eq!(change.is_breaking(), severity == Severity::Breaking, "{}", change);
+  }
+}
+
+#[test]
+fn detects_changes() {
+  let table: Vec<(Edit, Vec<Change>)> = vec![
+    (|_| (), vec![]),
+    (|c| c.name = "Coin".into(), vec![Change::ContractRenamed { from: "Token".into(), to: "Coin".into() }]),
+    (|c| c.version = 2, vec![Change::StateVersionChanged { from: 1, to: 2 }]),
+    (|c| c.state[1].field_type = "u128".into(), vec![Change::StateFieldsChanged {
+      from: strings(&["balances: OrderedMap<Address,u64>", "supply: u64"]),
+      to: strings(&["balances: OrderedMap<Address,u64>", "supply: u128"]),
+      migrated: false
+    }]),
+    (|c| { c.state.push(field("paused", "bool")); c.version = 2; }, vec![
+      Change::StateVersionChanged { from: 1, to: 2 },
+      Change::StateFieldsChanged {
+        from: strings(&["balances: OrderedMap<Address,u64>", "supply: u64"]),
+        to: strings(&["balances: OrderedMap<Address,u64>", "supply: u64", "paused: bool"]),
+        migrated: true
+      }
+    ]),
+    (|c| c.state.swap(0, 1), vec![Change::StateFieldsChanged {
+      from: strings(&["balances: OrderedMap<Address,u64>", "supply: u64"]),
+      to: strings(&["supply: u64", "balances: OrderedMap<Address,u64>"]),
+      migrated: false
+    }]),
+    // manifests from before the state was recorded
+    (|c| c.state.clear(), vec![]),
+    (|c| c.abi = Abi::Binary, vec![Change::AbiChanged { from: Abi::Json, to: Abi::Binary }]),
+    (|c| c.methods[0].abi = Abi::Binary, vec![Change::MethodAbiChanged { method: "transfer".into(), from: Abi::Json, to: Abi::Binary }]),
+    (|c| { c.abi = Abi::Binary; c.methods[0].abi = Abi::Binary; }, vec![Change::AbiChanged { from: Abi::Json, to: Abi::Binary }]),
+    (|c| c.methods.push(method("mint", &[], "void", false)), vec![Change::MethodAdded("mint".into())]),
+    (|c| { c.methods.remove(0); }, vec![Change::MethodRemoved("transfer".into())]),
+    (|c| c.methods[0].name = "send".into(), vec![Change::MethodRenamed { from: "transfer".into(), to: "send".into() }]),
+    (|c| c.methods[0].method_params[1].param_type = "u32".into(), vec![Change::ParamTypesChanged {
+      method: "transfer".into(),
+      from: strings(&["Address", "u64"]),
+      to: strings(&["Address", "u32"])
+    }]),
+    (|c| c.methods[0].method_params[1].name = "value".into(), vec![Change::ParamNamesChanged {
+      method: "transfer".into(),
+      from: strings(&["to", "amount"]),
+      to: strings(&["to", "value"])
+    }]),
+    (|c| c.methods[1].return_value.value_type = "u128".into(), vec![Change::ReturnTypeChanged {
+      method: "balance_of".into(),
+      from: "u64".into(),
+      to: "u128".into()
+    }]),
+    (|c| c.methods[1].readonly = false, vec![Change::ReadonlyChanged { method: "balance_of".into(), readonly: false }]),
+    (|c| c.events.push(Event { name: "Mint".into(), param_count: 2 }), vec![Change::EventAdded("Mint".into())]),
+    (|c| c.events.clear(), vec![Change::EventRemoved("Transfer".into())]),
+    (|c| c.events[0].param_count = 4, vec![Change::EventParamsChanged { event: "Transfer".into(), from: 3, to: 4 }]),
+    (|c| c.interfaces.push("KRC721".into()), vec![Change::InterfaceAdded("KRC721".into())]),
+    (|c| c.interfaces.clear(), vec![Change::InterfaceRemoved("KRC20".into())])
+  ];
+
+  for (edit, expected) in table {
+    let old = contract();
+    let mut new = contract();
+    edit(&mut new);
+
+    let changes = compare(&old, &new);
+    assert_eq!(changes, expected);
+    assert_eq!(is_compatible(&changes), !expected.iter().any(|change| change.is_breaking()));
+  }
+}
+
+// the state comes from the struct's own part of the manifest
+#[test]
+fn state_part_merges_into_the_contract() {
+  let mut without_state = contract();
+  without_state.state.clear();
+
+  let state = Contract { state: contract().state, ..Contract::empty() };
+  let merged = merge_parts(vec![("state".into(), state), ("contract".into(), without_state)]);
+
+  assert_eq!(merged, contract());
+}
@@ -1,27 +1,30 @@
+#![cfg_attr(target_arch = "wasm32", no_std)]
+
 extern crate kryolite_smart_contract;
 
 use kryolite_smart_contract::*;
-use std::collections::{HashMap, HashSet};
 
+#[smart_contract]
 #[derive(Serialize, Clone)]
 pub struct KryoliteLottery {
   pub tickets_sold: u64,
-  pub ticket_price: u64,
+  pub ticket_price: Kryo,
   pub registration_open: bool,
-  pub tickets: HashMap<U256, Ticket>,
-  pub ticket_to_address: HashMap<U256, Address>,
-  pub address_to_tickets: HashMap<Address, HashSet<Ticket>>,
-  pub approved_transfers: HashMap<U256, Address>,
+  pub pausable: Pausable,
+  pub tickets: OrderedMap<U256, Ticket>,
+  pub ticket_to_address: IndexedMap<U256, Address>,
+  pub address_to_tickets: OrderedMap<Address, OrderedSet<Ticket>>,
+  pub approved_transfers: OrderedMap<U256, Address>,
   pub last_winner: Winner
 }
 
 #[derive(Serialize, Copy, Clone)]
 pub struct Winner {
   pub address: Address,
-  pub reward: u64
+  pub reward: Kryo
 }
 
-#[derive(Serialize, Clone, PartialEq, Eq, Hash)]
+#[derive(Serialize, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Ticket {
   pub token_id: U256,
   pub name: String
@@ -35,22 +38,25 @@ impl KryoliteLottery {
       tickets_sold: 0,
       ticket_price: 100kryo,
       registration_open: true,
-      tickets: HashMap::new(),
-      ticket_to_address: HashMap::new(),
-      address_to_tickets: HashMap::new(),
-      approved_transfers: HashMap::new(),
+      pausable: Pausable::new(),
+      tickets: OrderedMap::new(),
+      ticket_to_address: IndexedMap::new(),
+      address_to_tickets: OrderedMap::new(),
+      approved_transfers: OrderedMap::new(),
       last_winner: Winner {
         address: NULL_ADDRESS,
-        reward: 0
+        reward: Kryo::ZERO
       }
     }
   }
 
+  #[non_reentrant]
+  #[when_not_paused]
   pub fn buy_ticket(&mut self) {
-    require(TRANSACTION.value == self.ticket_price);
+    require(TRANSACTION.amount() == self.ticket_price);
     require(self.registration_open);
 
-    let fee: u64 = TRANSACTION.value / 100;
+    let fee = TRANSACTION.amount() / 100;
     CONTRACT.owner.transfer(fee); // small fee for owner
 
     let ticket = self.print_ticket();
@@ -58,21 +64,23 @@ impl KryoliteLottery {
     self.ticket_to_address.insert(ticket.token_id, TRANSACTION.from);
     self.tickets.insert(ticket.token_id, ticket.clone());
 
-    let tickets = self.address_to_tickets.entry(TRANSACTION.from).or_insert(HashSet::new());
+    let tickets = self.address_to_tickets.entry(TRANSACTION.from).or_insert(OrderedSet::new());
     tickets.insert(ticket.clone());
 
     KRC721Event::transfer(&CONTRACT.address, &TRANSACTION.from, &ticket.token_id);
   }
 
+  #[only_owner]
+  #[non_reentrant]
+  #[when_not_paused]
   pub fn draw_winner(&mut self) {
-    require(TRANSACTION.from == CONTRACT.owner);
     require(self.tickets.len() > 0);
 
-    let prize_pool = CONTRACT.balance;
-    let count = self.ticket_to_address.len() as f32;
-    let random = (rand() * count) as usize;
+    let prize_pool = Kryo::from(balance());
+    let count = self.ticket_to_address.len() as u64;
+    let random = random_range(0, count) as usize;
 
-    let winner = self.ticket_to_address.values().nth(random).unwrap();
+    let (_, winner) = self.ticket_to_address.get_index(random).unwrap();
 
     for (token_id, owner) in &self.ticket_to_address {
       KRC721Event::consume(&owner, &token_id);
@@ -93,28 +101,36 @@ impl KryoliteLottery {
     self.approved_transfers.clear();
   }
 
- pub fn open_registration(&mut self) {
-    require(TRANSACTION.from == CONTRACT.owner);
+  #[only_owner]
+  pub fn open_registration(&mut self) {
     self.registration_open = true;
     
     event!(RegistrationsOpen);
   }
 
+  #[only_owner]
   pub fn close_registration(&mut self) {
-    require(TRANSACTION.from == CONTRACT.owner);
     self.registration_open = false;
 
     event!(RegistrationsClosed);
   }
 
-  pub fn set_ticket_price(&mut self, new_price: u64) {
-    require(TRANSACTION.from == CONTRACT.owner);
+  #[only_owner]
+  pub fn set_ticket_price(&mut self, new_price: Kryo) {
     require(!self.registration_open);
     require(self.tickets.len() == 0);
 
     self.ticket_price = new_price;
   }
 
+  pub fn pause(&mut self) {
+    self.pausable.pause();
+  }
+
+  pub fn unpause(&mut self) {
+    self.pausable.unpause();
+  }
+
   pub fn tickets_sold(&self) -> usize {
     self.tickets.len()
   }
@@ -193,7 +209,7 @@ impl KRC721 for KryoliteLottery {
     self.ticket_to_address.remove(&token_id);
 
     
-    let recipient_tickets = self.address_to_tickets.entry(to).or_insert(HashSet::new());
+    let recipient_tickets = self.address_to_tickets.entry(to).or_insert(OrderedSet::new());
     recipient_tickets.insert(ticket.clone());
     self.ticket_to_address.insert(token_id, to);
 
@@ -0,0 +1,93 @@
+#![cfg_attr(target_arch = "wasm32", no_std)]
+
+extern crate kryolite_smart_contract;
+
+use kryolite_smart_contract::*;
+
+/// Treasury wallet that only moves funds with M-of-N owner approval.
+///
+/// The deployer starts out as the only owner with a threshold of one. Further owners and a
+/// higher threshold are set up through proposals like any other change, the contract owner
+/// has no special rights after deployment.
+#[smart_contract]
+#[derive(Serialize, Clone)]
+pub struct KryoliteMultisig {
+  pub multisig: Multisig
+}
+
+#[smart_contract]
+impl KryoliteMultisig {
+
+  pub fn new() -> KryoliteMultisig {
+    KryoliteMultisig {
+      multisig: Multisig::new(vec![CONTRACT.owner], 1)
+    }
+  }
+
+  pub fn deposit(&mut self) {
+    require(TRANSACTION.amount() > Kryo::ZERO);
+
+    event!(Deposit, &TRANSACTION.from, &TRANSACTION.amount());
+  }
+
+  // executes right away when the threshold is already met
+  #[non_reentrant]
+  pub fn propose_transfer(&mut self, to: Address, amount: Kryo) -> u64 {
+    self.multisig.submit(Action::Transfer { to, amount })
+  }
+
+  #[non_reentrant]
+  pub fn propose_call(&mut self, contract: Address, method: String, params: String, amount: Kryo) -> u64 {
+    self.multisig.submit(Action::Call { contract, method, params, amount })
+  }
+
+  #[non_reentrant]
+  pub fn propose_add_owner(&mut self, owner: Address) -> u64 {
+    self.multisig.submit(Action::AddOwner(owner))
+  }
+
+  #[non_reentrant]
+  pub fn propose_remove_owner(&mut self, owner: Address) -> u64 {
+    self.multisig.submit(Action::RemoveOwner(owner))
+  }
+
+  #[non_reentrant]
+  pub fn propose_threshold(&mut self, threshold: u32) -> u64 {
+    self.multisig.submit(Action::ChangeThreshold(threshold))
+  }
+
+  #[non_reentrant]
+  pub fn confirm(&mut self, id: u64) {
+    self.multisig.confirm(id);
+  }
+
+  pub fn revoke(&mut self, id: u64) {
+    self.multisig.revoke(id);
+  }
+
+  #[non_reentrant]
+  pub fn execute(&mut self, id: u64) {
+    self.multisig.execute(id);
+  }
+
+  pub fn get_owners(&self) -> Vec<Address> {
+    self.multisig.owners().to_vec()
+  }
+
+  pub fn get_threshold(&self) -> u32 {
+    self.multisig.threshold()
+  }
+
+  pub fn get_proposal(&self, id: u64) -> Proposal {
+    require(self.multisig.proposal(id).is_some());
+    self.multisig.proposal(id).unwrap().clone()
+  }
+
+  pub fn get_pending(&self) -> Vec<Proposal> {
+    self.multisig.pending().into_iter().cloned().collect()
+  }
+
+  pub fn get_balance(&self) -> Kryo {
+    Kryo::from(balance())
+  }
+}
@@ -0,0 +1,540 @@
+//! Compact binary encoding for method arguments and return values of contracts declared with
+//! `#[smart_contract(abi = "binary")]`.
+//!
+//! The layout follows Borsh: integers and floats are little-endian with their natural width,
+//! `bool` and enum variant tags are one byte, strings, byte buffers, sequences and maps are
+//! prefixed with their length as `u32`, and `Option` is a `0`/`1` tag followed by the value.
+//! Structs and tuples are their fields in declaration order with no prefix. `Address`, `U256`
+//! and `Kryo` are written as their raw bytes and units instead of the strings used in JSON.
+//!
+//! The encoding isn't self-describing, a value can only be read back as the type it was written as.
+
+use alloc::{string::{String, ToString}, vec::Vec};
+use core::fmt;
+
+use serde::{ser, de, Serialize, Deserialize};
+use serde::de::{DeserializeSeed, IntoDeserializer, Visitor};
+use super::{require, __return};
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum AbiError {
+  /// Input ended before the value was complete.
+  Eof,
+  /// Input has bytes left after the value.
+  TrailingBytes,
+  InvalidBool(u8),
+  InvalidOption(u8),
+  InvalidChar(u32),
+  InvalidUtf8,
+  /// Sequences and maps need their length up front.
+  UnknownLength,
+  /// Only 256 enum variants fit in the tag.
+  TooManyVariants,
+  /// Length doesn't fit in the `u32` prefix.
+  TooLong,
+  /// Formats that need the type from the data itself, like `deserialize_any`.
+  Unsupported(&'static str),
+  Message(String)
+}
+
+impl fmt::Display for AbiError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      AbiError::Eof => write!(f, "unexpected end of input"),
+      AbiError::TrailingBytes => write!(f, "trailing bytes after value"),
+      AbiError::InvalidBool(byte) => write!(f, "invalid bool {}", byte),
+      AbiError::InvalidOption(byte) => write!(f, "invalid option tag {}", byte),
+      AbiError::InvalidChar(value) => write!(f, "invalid char {:#x}", value),
+      AbiError::InvalidUtf8 => write!(f, "string is not valid utf-8"),
+      AbiError::UnknownLength => write!(f, "sequence length must be known"),
+      AbiError::TooManyVariants => write!(f, "enum has more than 256 variants"),
+      AbiError::TooLong => write!(f, "length does not fit in u32"),
+      AbiError::Unsupported(what) => write!(f, "{} is not supported by the binary abi", what),
+      AbiError::Message(msg) => write!(f, "{}", msg)
+    }
+  }
+}
+
+impl core::error::Error for AbiError {}
+
+impl ser::Error for AbiError {
+  fn custom<T: fmt::Display>(msg: T) -> Self {
+    AbiError::Message(msg.to_string())
+  }
+}
+
+impl de::Error for AbiError {
+  fn custom<T: fmt::Display>(msg: T) -> Self {
+    AbiError::Message(msg.to_string())
+  }
+}
+
+pub fn abi_encode<T: Serialize + ?Sized>(value: &T) -> Result<Vec<u8>, AbiError> {
+  let mut serializer = BinarySerializer { output: Vec::new() };
+  value.serialize(&mut serializer)?;
+  Ok(serializer.output)
+}
+
+pub fn abi_decode<'de, T: Deserialize<'de>>(bytes: &'de [u8]) -> Result<T, AbiError> {
+  let mut deserializer = BinaryDeserializer { input: bytes };
+  let value = T::deserialize(&mut deserializer)?;
+
+  if !deserializer.input.is_empty() {
+    return Err(AbiError::TrailingBytes);
+  }
+
+  Ok(value)
+}
+
+/// Reads the arguments the host passed to a binary abi method, reverting on malformed input.
+///
+/// # Safety
+/// `args` must point to `args_len` readable bytes.
+pub unsafe fn decode_args<T: de::DeserializeOwned>(args: *const u8, args_len: usize) -> T {
+  let bytes = match args_len {
+    0 => &[],
+    _ => core::slice::from_raw_parts(args, args_len)
+  };
+
+  let args = abi_decode(bytes);
+  require(args.is_ok());
+  args.unwrap()
+}
+
+/// Hands the binary encoded return value of a method to the host.
+pub fn push_return_binary<T: Serialize + ?Sized>(value: &T) {
+  let bytes = abi_encode(value);
+  require(bytes.is_ok());
+
+  let bytes = bytes.unwrap();
+
+  unsafe {
+    __return(bytes.as_ptr(), bytes.len());
+  }
+}
+
+pub struct BinarySerializer {
+  output: Vec<u8>
+}
+
+impl BinarySerializer {
+  fn write_len(&mut self, len: usize) -> Result<(), AbiError> {
+    let len = u32::try_from(len).map_err(|_| AbiError::TooLong)?;
+    self.output.extend_from_slice(&len.to_le_bytes());
+    Ok(())
+  }
+
+  fn write_tag(&mut self, variant_index: u32) -> Result<(), AbiError> {
+    let tag = u8::try_from(variant_index).map_err(|_| AbiError::TooManyVariants)?;
+    self.output.push(tag);
+    Ok(())
+  }
+}
+
+macro_rules! serialize_le {
+  ($($method:ident: $ty:ty),*) => {
+    $(
+      fn $method(self, v: $ty) -> Result<(), AbiError> {
+        self.output.extend_from_slice(&v.to_le_bytes());
+        Ok(())
+      }
+    )*
+  };
+}
+
+impl ser::Serializer for &mut BinarySerializer {
+  type Ok = ();
+  type Error = AbiError;
+
+  type SerializeSeq = Self;
+  type SerializeTuple = Self;
+  type SerializeTupleStruct = Self;
+  type SerializeTupleVariant = Self;
+  type SerializeMap = Self;
+  type SerializeStruct = Self;
+  type SerializeStructVariant = Self;
+
+  serialize_le!(
+    serialize_i8: i8, serialize_i16: i16, serialize_i32: i32, serialize_i64: i64, serialize_i128: i128,
+    serialize_u8: u8, serialize_u16: u16, serialize_u32: u32, serialize_u64: u64, serialize_u128: u128,
+    serialize_f32: f32, serialize_f64: f64
+  );
+
+  fn serialize_bool(self, v: bool) -> Result<(), AbiError> {
+    self.output.push(v as u8);
+    Ok(())
+  }
+
+  fn serialize_char(self, v: char) -> Result<(), AbiError> {
+    self.serialize_u32(v as u32)
+  }
+
+  fn serialize_str(self, v: &str) -> Result<(), AbiError> {
+    self.serialize_bytes(v.as_bytes())
+  }
+
+  fn serialize_bytes(self, v: &[u8]) -> Result<(), AbiError> {
+    self.write_len(v.len())?;
+    self.output.extend_from_slice(v);
+    Ok(())
+  }
+
+  fn serialize_none(self) -> Result<(), AbiError> {
+    self.output.push(0);
+    Ok(())
+  }
+
+  fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<(), AbiError> {
+    self.output.push(1);
+    value.serialize(self)
+  }
+
+  fn serialize_unit(self) -> Result<(), AbiError> {
+    Ok(())
+  }
+
+  fn serialize_unit_struct(self, _name: &'static str) -> Result<(), AbiError> {
+    Ok(())
+  }
+
+  fn serialize_unit_variant(self, _name: &'static str, variant_index: u32, _variant: &'static str) -> Result<(), AbiError> {
+    self.write_tag(variant_index)
+  }
+
+  fn serialize_newtype_struct<T: Serialize + ?Sized>(self, _name: &'static str, value: &T) -> Result<(), AbiError> {
+    value.serialize(self)
+  }
+
+  fn serialize_newtype_variant<T: Serialize + ?Sized>(self, _name: &'static str, variant_index: u32, _variant: &'static str, value: &T) -> Result<(), AbiError> {
+    self.write_tag(variant_index)?;
+    value.serialize(self)
+  }
+
+  fn serialize_seq(self, len: Option<usize>) -> Result<Self, AbiError> {
+    self.write_len(len.ok_or(AbiError::UnknownLength)?)?;
+    Ok(self)
+  }
+
+  fn serialize_tuple(self, _len: usize) -> Result<Self, AbiError> {
+    Ok(self)
+  }
+
+  fn serialize_tuple_struct(self, _name: &'static str, _len: usize) -> Result<Self, AbiError> {
+    Ok(self)
+  }
+
+  fn serialize_tuple_variant(self, _name: &'static str, variant_index: u32, _variant: &'static str, _len: usize) -> Result<Self, AbiError> {
+    self.write_tag(variant_index)?;
+    Ok(self)
+  }
+
+  fn serialize_map(self, len: Option<usize>) -> Result<Self, AbiError> {
+    self.write_len(len.ok_or(AbiError::UnknownLength)?)?;
+    Ok(self)
+  }
+
+  fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Self, AbiError> {
+    Ok(self)
+  }
+
+  fn serialize_struct_variant(self, _name: &'static str, variant_index: u32, _variant: &'static str, _len: usize) -> Result<Self, AbiError> {
+    self.write_tag(variant_index)?;
+    Ok(self)
+  }
+
+  fn is_human_readable(&self) -> bool {
+    false
+  }
+}
+
+// every compound value is just its elements one after another
+macro_rules! serialize_elements {
+  ($($trait:ident::$method:ident),*) => {
+    $(
+      impl ser::$trait for &mut BinarySerializer {
+        type Ok = ();
+        type Error = AbiError;
+
+        fn $method<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), AbiError> {
+          value.serialize(&mut **self)
+        }
+
+        fn end(self) -> Result<(), AbiError> {
+          Ok(())
+        }
+      }
+    )*
+  };
+}
+
+serialize_elements!(
+  SerializeSeq::serialize_element,
+  SerializeTuple::serialize_element,
+  SerializeTupleStruct::serialize_field,
+  SerializeTupleVariant::serialize_field
+);
+
+impl ser::SerializeMap for &mut BinarySerializer {
+  type Ok = ();
+  type Error = AbiError;
+
+  fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), AbiError> {
+    key.serialize(&mut **self)
+  }
+
+  fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), AbiError> {
+    value.serialize(&mut **self)
+  }
+
+  fn end(self) -> Result<(), AbiError> {
+    Ok(())
+  }
+}
+
+impl ser::SerializeStruct for &mut BinarySerializer {
+  type Ok = ();
+  type Error = AbiError;
+
+  fn serialize_field<T: Serialize + ?Sized>(&mut self, _key: &'static str, value: &T) -> Result<(), AbiError> {
+    value.serialize(&mut **self)
+  }
+
+  fn end(self) -> Result<(), AbiError> {
+    Ok(())
+  }
+}
+
+impl ser::SerializeStructVariant for &mut BinarySerializer {
+  type Ok = ();
+  type Error = AbiError;
+
+  fn serialize_field<T: Serialize + ?Sized>(&mut self, _key: &'static str, value: &T) -> Result<(), AbiError> {
+    value.serialize(&mut **self)
+  }
+
+  fn end(self) -> Result<(), AbiError> {
+    Ok(())
+  }
+}
+
+pub struct BinaryDeserializer<'de> {
+  input: &'de [u8]
+}
+
+impl<'de> BinaryDeserializer<'de> {
+  fn take(&mut self, len: usize) -> Result<&'de [u8], AbiError> {
+    if self.input.len() < len {
+      return Err(AbiError::Eof);
+    }
+
+    let (bytes, rest) = self.input.split_at(len);
+    self.input = rest;
+    Ok(bytes)
+  }
+
+  fn take_array<const N: usize>(&mut self) -> Result<[u8; N], AbiError> {
+    Ok(self.take(N)?.try_into().unwrap())
+  }
+
+  fn read_len(&mut self) -> Result<usize, AbiError> {
+    Ok(u32::from_le_bytes(self.take_array()?) as usize)
+  }
+
+  fn read_bytes(&mut self) -> Result<&'de [u8], AbiError> {
+    let len = self.read_len()?;
+    self.take(len)
+  }
+
+  fn read_str(&mut self) -> Result<&'de str, AbiError> {
+    core::str::from_utf8(self.read_bytes()?).map_err(|_| AbiError::InvalidUtf8)
+  }
+}
+
+macro_rules! deserialize_le {
+  ($($method:ident: $ty:ty => $visit:ident),*) => {
+    $(
+      fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, AbiError> {
+        visitor.$visit(<$ty>::from_le_bytes(self.take_array()?))
+      }
+    )*
+  };
+}
+
+impl<'de> de::Deserializer<'de> for &mut BinaryDeserializer<'de> {
+  type Error = AbiError;
+
+  deserialize_le!(
+    deserialize_i8: i8 => visit_i8, deserialize_i16: i16 => visit_i16, deserialize_i32: i32 => visit_i32,
+    deserialize_i64: i64 => visit_i64, deserialize_i128: i128 => visit_i128,
+    deserialize_u8: u8 => visit_u8, deserialize_u16: u16 => visit_u16, deserialize_u32: u32 => visit_u32,
+    deserialize_u64: u64 => visit_u64, deserialize_u128: u128 => visit_u128,
+    deserialize_f32: f32 => visit_f32, deserialize_f64: f64 => visit_f64
+  );
+
+  fn deserialize_any<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, AbiError> {
+    Err(AbiError::Unsupported("deserialize_any"))
+  }
+
+  fn deserialize_ignored_any<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, AbiError> {
+    Err(AbiError::Unsupported("ignoring values"))
+  }
+
+  fn deserialize_identifier<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, AbiError> {
+    Err(AbiError::Unsupported("identifiers"))
+  }
+
+  fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, AbiError> {
+    match self.take(1)?[0] {
+      0 => visitor.visit_bool(false),
+      1 => visitor.visit_bool(true),
+      byte => Err(AbiError::InvalidBool(byte))
+    }
+  }
+
+  fn deserialize_char<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, AbiError> {
+    let value = u32::from_le_bytes(self.take_array()?);
+    visitor.visit_char(char::from_u32(value).ok_or(AbiError::InvalidChar(value))?)
+  }
+
+  fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, AbiError> {
+    visitor.visit_borrowed_str(self.read_str()?)
+  }
+
+  fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, AbiError> {
+    self.deserialize_str(visitor)
+  }
+
+  fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, AbiError> {
+    visitor.visit_borrowed_bytes(self.read_bytes()?)
+  }
+
+  fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, AbiError> {
+    self.deserialize_bytes(visitor)
+  }
+
+  fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, AbiError> {
+    match self.take(1)?[0] {
+      0 => visitor.visit_none(),
+      1 => visitor.visit_some(self),
+      byte => Err(AbiError::InvalidOption(byte))
+    }
+  }
+
+  fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, AbiError> {
+    visitor.visit_unit()
+  }
+
+  fn deserialize_unit_struct<V: Visitor<'de>>(self, _name: &'static str, visitor: V) -> Result<V::Value, AbiError> {
+    visitor.visit_unit()
+  }
+
+  fn deserialize_newtype_struct<V: Visitor<'de>>(self, _name: &'static str, visitor: V) -> Result<V::Value, AbiError> {
+    visitor.visit_newtype_struct(self)
+  }
+
+  fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, AbiError> {
+    let len = self.read_len()?;
+    visitor.visit_seq(Elements { de: self, remaining: len })
+  }
+
+  fn deserialize_tuple<V: Visitor<'de>>(self, len: usize, visitor: V) -> Result<V::Value, AbiError> {
+    visitor.visit_seq(Elements { de: self, remaining: len })
+  }
+
+  fn deserialize_tuple_struct<V: Visitor<'de>>(self, _name: &'static str, len: usize, visitor: V) -> Result<V::Value, AbiError> {
+    self.deserialize_tuple(len, visitor)
+  }
+
+  fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, AbiError> {
+    let len = self.read_len()?;
+    visitor.visit_map(Elements { de: self, remaining: len })
+  }
+
+  fn deserialize_struct<V: Visitor<'de>>(self, _name: &'static str, fields: &'static [&'static str], visitor: V) -> Result<V::Value, AbiError> {
+    self.deserialize_tuple(fields.len(), visitor)
+  }
+
+  fn deserialize_enum<V: Visitor<'de>>(self, _name: &'static str, _variants: &'static [&'static str], visitor: V) -> Result<V::Value, AbiError> {
+    visitor.visit_enum(self)
+  }
+
+  fn is_human_readable(&self) -> bool {
+    false
+  }
+}
+
+// elements of a sequence, tuple, struct or map with a known count
+struct Elements<'a, 'de> {
+  de: &'a mut BinaryDeserializer<'de>,
+  remaining: usize
+}
+
+impl<'de, 'a> de::SeqAccess<'de> for Elements<'a, 'de> {
+  type Error = AbiError;
+
+  fn next_element_seed<T: DeserializeSeed<'de>>(&mut self, seed: T) -> Result<Option<T::Value>, AbiError> {
+    if self.remaining == 0 {
+      return Ok(None);
+    }
+
+    self.remaining -= 1;
+    seed.deserialize(&mut *self.de).map(Some)
+  }
+
+  fn size_hint(&self) -> Option<usize> {
+    // the length comes from the input, don't let it preallocate more than can be there
+    Some(self.remaining.min(self.de.input.len()))
+  }
+}
+
+impl<'de, 'a> de::MapAccess<'de> for Elements<'a, 'de> {
+  type Error = AbiError;
+
+  fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>, AbiError> {
+    if self.remaining == 0 {
+      return Ok(None);
+    }
+
+    self.remaining -= 1;
+    seed.deserialize(&mut *self.de).map(Some)
+  }
+
+  fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, AbiError> {
+    seed.deserialize(&mut *self.de)
+  }
+
+  fn size_hint(&self) -> Option<usize> {
+    Some(self.remaining.min(self.de.input.len()))
+  }
+}
+
+impl<'de> de::EnumAccess<'de> for &mut BinaryDeserializer<'de> {
+  type Error = AbiError;
+  type Variant = Self;
+
+  fn variant_seed<V: DeserializeSeed<'de>>(self, seed: V) -> Result<(V::Value, Self), AbiError> {
+    let tag = self.take(1)?[0] as u32;
+    let variant = seed.deserialize(IntoDeserializer::<AbiError>::into_deserializer(tag))?;
+    Ok((variant, self))
+  }
+}
+
+impl<'de> de::VariantAccess<'de> for &mut BinaryDeserializer<'de> {
+  type Error = AbiError;
+
+  fn unit_variant(self) -> Result<(), AbiError> {
+    Ok(())
+  }
+
+  fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value, AbiError> {
+    seed.deserialize(self)
+  }
+
+  fn tuple_variant<V: Visitor<'de>>(self, len: usize, visitor: V) -> Result<V::Value, AbiError> {
+    de::Deserializer::deserialize_tuple(self, len, visitor)
+  }
+
+  fn struct_variant<V: Visitor<'de>>(self, fields: &'static [&'static str], visitor: V) -> Result<V::Value, AbiError> {
+    de::Deserializer::deserialize_tuple(self, fields.len(), visitor)
+  }
+}
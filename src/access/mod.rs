@@ -0,0 +1,91 @@
+
+use alloc::string::{String, ToString};
+use serde::{Serialize, Deserialize};
+use super::{Address, OrderedMap, OrderedSet, NULL_ADDRESS, CONTRACT, TRANSACTION, require, append_event, publish_event, set_owner, event};
+
+/// Role that is allowed to grant and revoke every other role (next to the contract owner).
+pub const DEFAULT_ADMIN_ROLE: &str = "admin";
+
+/// Role based access control, stored as a field named `access_control` in the contract state
+/// so that `#[only_role("...")]` methods can find it.
+#[derive(Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct AccessControl {
+  roles: OrderedMap<String, OrderedSet<Address>>
+}
+
+impl AccessControl {
+  pub fn new() -> AccessControl {
+    AccessControl {
+      roles: OrderedMap::new()
+    }
+  }
+
+  pub fn has_role(&self, role: &str, account: &Address) -> bool {
+    match self.roles.get(role) {
+      Some(members) => members.contains(account),
+      None => false
+    }
+  }
+
+  /// Grants `role` to `account`. Caller must be the contract owner or hold `DEFAULT_ADMIN_ROLE`.
+  pub fn grant_role(&mut self, role: &str, account: Address) {
+    self.require_admin();
+
+    let members = self.roles.entry(role.to_string()).or_default();
+
+    if members.insert(account) {
+      event!(RoleGranted, &role, &account, &TRANSACTION.from);
+    }
+  }
+
+  /// Revokes `role` from `account`. Caller must be the contract owner or hold `DEFAULT_ADMIN_ROLE`.
+  pub fn revoke_role(&mut self, role: &str, account: Address) {
+    self.require_admin();
+    self.remove_role(role, &account);
+  }
+
+  /// Gives up `role` held by the caller.
+  pub fn renounce_role(&mut self, role: &str) {
+    self.remove_role(role, &TRANSACTION.from);
+  }
+
+  fn remove_role(&mut self, role: &str, account: &Address) {
+    let removed = match self.roles.get_mut(role) {
+      Some(members) => members.remove(account),
+      None => false
+    };
+
+    if removed {
+      event!(RoleRevoked, &role, account, &TRANSACTION.from);
+    }
+  }
+
+  fn require_admin(&self) {
+    require(is_owner(&TRANSACTION.from) || self.has_role(DEFAULT_ADMIN_ROLE, &TRANSACTION.from));
+  }
+}
+
+pub fn is_owner(account: &Address) -> bool {
+  CONTRACT.owner == *account
+}
+
+/// Hands the contract over to `new_owner`. Only callable by the current owner.
+pub fn transfer_ownership(new_owner: Address) {
+  require(is_owner(&TRANSACTION.from));
+  require(new_owner != NULL_ADDRESS);
+
+  let previous_owner = CONTRACT.owner;
+  set_owner(new_owner);
+
+  event!(OwnershipTransferred, &previous_owner, &new_owner);
+}
+
+/// Leaves the contract without an owner, disabling every `#[only_owner]` method for good.
+pub fn renounce_ownership() {
+  require(is_owner(&TRANSACTION.from));
+
+  let previous_owner = CONTRACT.owner;
+  set_owner(NULL_ADDRESS);
+
+  event!(OwnershipTransferred, &previous_owner, &NULL_ADDRESS);
+}
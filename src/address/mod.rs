@@ -1,20 +1,34 @@
-use super::{__transfer, B32};
+use alloc::{borrow::ToOwned, string::String};
+use super::{__transfer, __call, balance_of, B32};
 use serde::{Serialize, Deserialize, de::Visitor};
 
 pub static NULL_ADDRESS: Address = Address([0; 26]);
 
 #[repr(C)]
-#[derive(Copy, Clone, Hash, Eq, PartialEq)]
+#[derive(Copy, Clone, Hash, Eq, PartialEq, Ord, PartialOrd)]
 pub struct Address(pub [u8; 26]);
 
-#[allow(dead_code)]
+#[allow(dead_code, clippy::len_without_is_empty)]
 impl Address {
-  pub fn transfer(&self, amount :u64) {
+  pub fn transfer(&self, amount: impl Into<u64>) {
     unsafe {
-      __transfer(self as *const Address, amount);
+      __transfer(self as *const Address, amount.into());
     }
   }
 
+  /// Calls `method` of the contract at this address, `params` is a JSON array of the arguments.
+  /// `value` is sent along like with `transfer`. Returns false if the callee reverted, none of its
+  /// changes are kept in that case.
+  pub fn call(&self, method: &str, params: &str, value: impl Into<u64>) -> bool {
+    unsafe {
+      __call(self as *const Address, method.as_ptr(), method.len(), params.as_ptr(), params.len(), value.into())
+    }
+  }
+
+  pub fn balance(&self) -> u64 {
+    balance_of(self)
+  }
+
   pub fn as_bytes(&self) -> &[u8] {
     &self.0
   }
@@ -30,13 +44,13 @@ impl Address {
   }
 
   pub fn as_ptr(&self) -> *const u8 {
-    self.0.as_ptr() as *const u8
+    self.0.as_ptr()
   }
 }
 
 impl AsRef<[u8]> for Address {
   fn as_ref(&self) -> &[u8] {
-    unsafe { std::slice::from_raw_parts(self.as_ptr(), self.len()) }
+    unsafe { core::slice::from_raw_parts(self.as_ptr(), self.len()) }
   }
 }
 
@@ -44,6 +58,11 @@ impl Serialize for Address {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: serde::Serializer {
+          // binary abi keeps the raw bytes
+          if !serializer.is_human_readable() {
+            return self.0.serialize(serializer);
+          }
+
           serializer.serialize_str(self.as_string().as_str())
     }
 }
@@ -52,6 +71,10 @@ impl <'de> Deserialize<'de> for Address {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
         D: serde::Deserializer<'de> {
+        if !deserializer.is_human_readable() {
+          return Deserialize::deserialize(deserializer).map(Address);
+        }
+
         deserializer.deserialize_str(StringVisitor)
     }
 }
@@ -61,7 +84,7 @@ struct StringVisitor;
 impl<'de> Visitor<'de> for StringVisitor {
     type Value = Address;
 
-    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+    fn expecting(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
         write!(formatter, "a string represents Address")
     }
 
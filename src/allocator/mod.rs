@@ -0,0 +1,172 @@
+//! Heap of the contract, grown page by page from wasm memory.
+//!
+//! The contract state stays in linear memory between calls and keeps allocating as it grows,
+//! so freed memory has to be reused. An allocation the host won't grow memory for returns null,
+//! and a null from the global allocator ends up in the panic handler, which reverts the call.
+
+use core::{alloc::{GlobalAlloc, Layout}, cell::UnsafeCell, mem::size_of, ptr::null_mut};
+
+const PAGE_SIZE: usize = 65536;
+
+// grows memory by `pages`, returns the address of the new memory
+#[cfg(target_arch = "wasm32")]
+fn grow_memory(pages: usize) -> Option<usize> {
+  match core::arch::wasm32::memory_grow(0, pages) {
+    usize::MAX => None,
+    previous => Some(previous * PAGE_SIZE)
+  }
+}
+
+// contracts only allocate from wasm memory, native builds use the system allocator
+#[cfg(not(target_arch = "wasm32"))]
+fn grow_memory(_pages: usize) -> Option<usize> {
+  None
+}
+
+fn align_up(addr: usize, align: usize) -> Option<usize> {
+  Some(addr.checked_add(align - 1)? & !(align - 1))
+}
+
+fn pages_for(size: usize) -> usize {
+  size.div_ceil(PAGE_SIZE)
+}
+
+// free memory region, stored in the region itself
+struct Region {
+  size: usize,
+  next: *mut Region
+}
+
+// every region and allocation is a multiple of this, so splitting one always leaves
+// pieces that can hold a `Region` again
+const GRANULE: usize = size_of::<Region>();
+
+/// First fit allocator over a list of free regions sorted by address. Freed memory is merged
+/// with its neighbours so the heap doesn't fragment into pieces too small to reuse.
+pub struct FreeListAllocator {
+  head: UnsafeCell<*mut Region>
+}
+
+// wasm contracts are single threaded
+unsafe impl Sync for FreeListAllocator {}
+
+impl FreeListAllocator {
+  pub const fn new() -> FreeListAllocator {
+    FreeListAllocator {
+      head: UnsafeCell::new(null_mut())
+    }
+  }
+
+  fn granules(layout: Layout) -> Option<(usize, usize)> {
+    let size = align_up(layout.size().max(1), GRANULE)?;
+    let align = layout.align().max(GRANULE);
+    Some((size, align))
+  }
+
+  // takes `size` bytes at `align` out of the first region that has room
+  unsafe fn take(&self, size: usize, align: usize) -> *mut u8 {
+    let mut link: *mut *mut Region = self.head.get();
+
+    while !(*link).is_null() {
+      let region = *link;
+      let start = region as usize;
+      let end = start + (*region).size;
+
+      if let Some(aligned) = align_up(start, align) {
+        if aligned.checked_add(size).is_some_and(|alloc_end| alloc_end <= end) {
+          let alloc_end = aligned + size;
+          let next = (*region).next;
+
+          // whatever is left after the allocation stays in the list in place of the region
+          let mut rest = next;
+
+          if alloc_end < end {
+            rest = alloc_end as *mut Region;
+            rest.write(Region { size: end - alloc_end, next });
+          }
+
+          if aligned > start {
+            (*region).size = aligned - start;
+            (*region).next = rest;
+          } else {
+            *link = rest;
+          }
+
+          return aligned as *mut u8;
+        }
+      }
+
+      link = &mut (*region).next;
+    }
+
+    null_mut()
+  }
+
+  // puts `size` bytes at `addr` back into the list, merging with the regions around it
+  unsafe fn give(&self, addr: usize, size: usize) {
+    let mut prev: *mut Region = null_mut();
+    let mut next = *self.head.get();
+
+    while !next.is_null() && (next as usize) < addr {
+      prev = next;
+      next = (*next).next;
+    }
+
+    let region = addr as *mut Region;
+    region.write(Region { size, next });
+
+    if !next.is_null() && addr + size == next as usize {
+      (*region).size += (*next).size;
+      (*region).next = (*next).next;
+    }
+
+    if prev.is_null() {
+      *self.head.get() = region;
+    } else if prev as usize + (*prev).size == addr {
+      (*prev).size += (*region).size;
+      (*prev).next = (*region).next;
+    } else {
+      (*prev).next = region;
+    }
+  }
+}
+
+impl Default for FreeListAllocator {
+  fn default() -> FreeListAllocator {
+    FreeListAllocator::new()
+  }
+}
+
+unsafe impl GlobalAlloc for FreeListAllocator {
+  unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+    let (size, align) = match FreeListAllocator::granules(layout) {
+      Some(granules) => granules,
+      None => return null_mut()
+    };
+
+    let ptr = self.take(size, align);
+
+    if !ptr.is_null() {
+      return ptr;
+    }
+
+    let pages = match size.checked_add(align) {
+      Some(needed) => pages_for(needed),
+      None => return null_mut()
+    };
+
+    match grow_memory(pages) {
+      Some(memory) => {
+        self.give(memory, pages * PAGE_SIZE);
+        self.take(size, align)
+      }
+      None => null_mut()
+    }
+  }
+
+  unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+    if let Some((size, _)) = FreeListAllocator::granules(layout) {
+      self.give(ptr as usize, size);
+    }
+  }
+}
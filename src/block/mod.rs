@@ -0,0 +1,44 @@
+use core::{ops::Deref, ptr::addr_of};
+
+use super::{U256, __remaining_gas};
+
+/// Block the current transaction is executed in, written by the node before the call.
+#[repr(C, packed)]
+#[derive(Copy, Clone, PartialEq)]
+pub struct BlockData {
+  pub height: u64,
+  /// Unix time in milliseconds.
+  pub timestamp: u64,
+  pub parent_hash: U256,
+  pub chain_id: u32
+}
+
+#[no_mangle]
+pub(crate) static mut _BLOCK: BlockData = BlockData {
+  height: 0,
+  timestamp: 0,
+  parent_hash: U256([0; 32]),
+  chain_id: 0
+};
+
+/// Reads `_BLOCK`, which the node writes before the call.
+pub struct BlockContext;
+
+pub static BLOCK: BlockContext = BlockContext;
+
+impl Deref for BlockContext {
+  type Target = BlockData;
+
+  fn deref(&self) -> &BlockData {
+    unsafe {
+      &*addr_of!(_BLOCK)
+    }
+  }
+}
+
+/// Gas left for the rest of the current call.
+pub fn remaining_gas() -> u64 {
+  unsafe {
+    __remaining_gas()
+  }
+}
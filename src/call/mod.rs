@@ -0,0 +1,46 @@
+use core::ptr::addr_of_mut;
+
+use super::RNG;
+
+pub(crate) static mut DEPTH: u32 = 0;
+
+/// Opened by every exported entry point the macros generate. Linear memory outlives a call,
+/// so state that only belongs to one call (the random stream) is reset when the outermost
+/// scope opens. Exported methods called from inside the contract open nested scopes that
+/// leave it alone.
+#[doc(hidden)]
+pub struct CallScope {
+  _private: ()
+}
+
+impl CallScope {
+  pub fn enter() -> CallScope {
+    unsafe {
+      let depth = &mut *addr_of_mut!(DEPTH);
+
+      if *depth == 0 {
+        reset_call_state();
+      }
+
+      *depth += 1;
+    }
+
+    CallScope { _private: () }
+  }
+}
+
+impl Drop for CallScope {
+  fn drop(&mut self) {
+    unsafe {
+      let depth = &mut *addr_of_mut!(DEPTH);
+      *depth = depth.saturating_sub(1);
+    }
+  }
+}
+
+pub(crate) fn reset_call_state() {
+  unsafe {
+    // reseeded from this call's __random_seed on the next draw
+    *addr_of_mut!(RNG) = None;
+  }
+}
@@ -0,0 +1,178 @@
+//! Collections with an iteration order that is the same on every node.
+//!
+//! `std::collections::HashMap` and `HashSet` iterate in an order that depends on the hasher,
+//! so anything derived from it (picking the n-th entry, serializing the state) can differ
+//! between nodes and builds. Contract state should use these instead, `#[smart_contract]` on the
+//! state struct warns about hash based collections in it.
+
+use core::{fmt, marker::PhantomData};
+use alloc::{collections::{BTreeMap, BTreeSet}, vec::Vec};
+
+use serde::{Serialize, Deserialize, de::{MapAccess, Visitor}, ser::SerializeMap};
+
+/// Map iterating in key order.
+pub type OrderedMap<K, V> = BTreeMap<K, V>;
+
+/// Set iterating in value order.
+pub type OrderedSet<T> = BTreeSet<T>;
+
+/// Map iterating in insertion order with O(1) access by position, e.g. for drawing a random entry
+/// with `map.get_index(random_range(0, map.len() as u64) as usize)`.
+///
+/// `remove` moves the last entry into the freed position to stay O(log n).
+#[derive(Clone, PartialEq)]
+pub struct IndexedMap<K: Ord + Clone, V> {
+  entries: Vec<(K, V)>,
+  positions: BTreeMap<K, usize>
+}
+
+impl<K: Ord + Clone, V> IndexedMap<K, V> {
+  pub fn new() -> IndexedMap<K, V> {
+    IndexedMap {
+      entries: Vec::new(),
+      positions: BTreeMap::new()
+    }
+  }
+
+  pub fn len(&self) -> usize {
+    self.entries.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.entries.is_empty()
+  }
+
+  pub fn contains_key(&self, key: &K) -> bool {
+    self.positions.contains_key(key)
+  }
+
+  pub fn get(&self, key: &K) -> Option<&V> {
+    self.positions.get(key).map(|pos| &self.entries[*pos].1)
+  }
+
+  pub fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+    self.positions.get(key).map(|pos| &mut self.entries[*pos].1)
+  }
+
+  pub fn get_index(&self, index: usize) -> Option<(&K, &V)> {
+    self.entries.get(index).map(|(key, value)| (key, value))
+  }
+
+  pub fn position(&self, key: &K) -> Option<usize> {
+    self.positions.get(key).copied()
+  }
+
+  /// Inserts or replaces the value of `key`, returning the old value. A replaced entry keeps its position.
+  pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+    match self.positions.get(&key) {
+      Some(pos) => Some(core::mem::replace(&mut self.entries[*pos].1, value)),
+      None => {
+        self.positions.insert(key.clone(), self.entries.len());
+        self.entries.push((key, value));
+        None
+      }
+    }
+  }
+
+  /// Value of `key`, inserting `default()` first if there is none.
+  pub fn get_or_insert_with(&mut self, key: K, default: impl FnOnce() -> V) -> &mut V {
+    let pos = match self.positions.get(&key) {
+      Some(pos) => *pos,
+      None => {
+        self.insert(key, default());
+        self.entries.len() - 1
+      }
+    };
+
+    &mut self.entries[pos].1
+  }
+
+  pub fn remove(&mut self, key: &K) -> Option<V> {
+    let pos = self.positions.remove(key)?;
+    let (_, value) = self.entries.swap_remove(pos);
+
+    if let Some((moved, _)) = self.entries.get(pos) {
+      self.positions.insert(moved.clone(), pos);
+    }
+
+    Some(value)
+  }
+
+  pub fn clear(&mut self) {
+    self.entries.clear();
+    self.positions.clear();
+  }
+
+  pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+    self.entries.iter().map(|(key, value)| (key, value))
+  }
+
+  pub fn keys(&self) -> impl Iterator<Item = &K> {
+    self.entries.iter().map(|(key, _)| key)
+  }
+
+  pub fn values(&self) -> impl Iterator<Item = &V> {
+    self.entries.iter().map(|(_, value)| value)
+  }
+}
+
+impl<K: Ord + Clone, V> Default for IndexedMap<K, V> {
+  fn default() -> IndexedMap<K, V> {
+    IndexedMap::new()
+  }
+}
+
+impl<'a, K: Ord + Clone, V> IntoIterator for &'a IndexedMap<K, V> {
+  type Item = (&'a K, &'a V);
+  type IntoIter = core::iter::Map<core::slice::Iter<'a, (K, V)>, fn(&'a (K, V)) -> (&'a K, &'a V)>;
+
+  fn into_iter(self) -> Self::IntoIter {
+    self.entries.iter().map(|(key, value)| (key, value))
+  }
+}
+
+// serialized as a map in insertion order, the positions are rebuilt when reading it back
+impl<K: Ord + Clone + Serialize, V: Serialize> Serialize for IndexedMap<K, V> {
+  fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+  where
+    S: serde::Serializer {
+      let mut map = serializer.serialize_map(Some(self.len()))?;
+
+      for (key, value) in &self.entries {
+        map.serialize_entry(key, value)?;
+      }
+
+      map.end()
+  }
+}
+
+impl<'de, K: Ord + Clone + Deserialize<'de>, V: Deserialize<'de>> Deserialize<'de> for IndexedMap<K, V> {
+  fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+  where
+    D: serde::Deserializer<'de> {
+      deserializer.deserialize_map(IndexedMapVisitor(PhantomData))
+  }
+}
+
+struct IndexedMapVisitor<K, V>(PhantomData<(K, V)>);
+
+impl<'de, K: Ord + Clone + Deserialize<'de>, V: Deserialize<'de>> Visitor<'de> for IndexedMapVisitor<K, V> {
+  type Value = IndexedMap<K, V>;
+
+  fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+    write!(formatter, "a map")
+  }
+
+  fn visit_map<A>(self, mut access: A) -> Result<Self::Value, A::Error>
+  where
+    A: MapAccess<'de>,
+  {
+    let mut map = IndexedMap::new();
+
+    while let Some((key, value)) = access.next_entry()? {
+      map.insert(key, value);
+    }
+
+    Ok(map)
+  }
+}
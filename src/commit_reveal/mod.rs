@@ -0,0 +1,164 @@
+use alloc::vec::Vec;
+use serde::{Serialize, Deserialize};
+use super::{Address, U256, OrderedMap, OrderedSet, TRANSACTION, Rng, Hasher, Sha256, sha256, require, is_owner, random_u256, append_event, publish_event, event};
+
+#[derive(Serialize, Deserialize, Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Phase {
+  Commit,
+  Reveal,
+  Finished
+}
+
+/// What happens to the deposit of a participant that committed but never revealed.
+#[derive(Serialize, Deserialize, Copy, Clone, PartialEq)]
+pub enum NonRevealPenalty {
+  /// Deposit stays in the contract balance, e.g. as part of a prize pool.
+  Forfeit,
+  /// Deposit is sent to the given address.
+  SendTo(Address),
+  /// Deposit is returned, the participant is only left out of the result.
+  Refund
+}
+
+/// Commit-reveal scheme for randomness that no single party controls.
+///
+/// Participants `commit(sha256(secret))` with a deposit while the round is in the commit phase
+/// and `reveal(secret)` once the owner has closed it. The seed combines every revealed secret
+/// with the host seed, so neither the participants nor the block producer can pick the outcome
+/// alone. Withholding a secret to influence the result costs the deposit as configured by the
+/// `NonRevealPenalty`, and the participant is left out of `participants()`.
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+pub struct CommitReveal {
+  phase: Phase,
+  deposit: u64,
+  penalty: NonRevealPenalty,
+  commitments: OrderedMap<Address, U256>,
+  committers: Vec<Address>,
+  used_commitments: OrderedSet<U256>,
+  revealed: Vec<Address>,
+  accumulator: U256,
+  seed: Option<U256>
+}
+
+impl CommitReveal {
+  pub fn new(deposit: u64, penalty: NonRevealPenalty) -> CommitReveal {
+    CommitReveal {
+      phase: Phase::Commit,
+      deposit,
+      penalty,
+      commitments: OrderedMap::new(),
+      committers: Vec::new(),
+      used_commitments: OrderedSet::new(),
+      revealed: Vec::new(),
+      accumulator: U256([0; 32]),
+      seed: None
+    }
+  }
+
+  pub fn phase(&self) -> Phase {
+    self.phase
+  }
+
+  /// Registers the caller's `sha256(secret)`. The transaction value must equal the deposit.
+  pub fn commit(&mut self, commitment: U256) {
+    require(self.phase == Phase::Commit);
+    require(TRANSACTION.value == self.deposit);
+    require(!self.commitments.contains_key(&TRANSACTION.from));
+    // copying someone else's commitment would let the copier cancel their secret out
+    require(self.used_commitments.insert(commitment));
+
+    self.commitments.insert(TRANSACTION.from, commitment);
+    self.committers.push(TRANSACTION.from);
+
+    event!(Committed, &TRANSACTION.from, &commitment);
+  }
+
+  /// Ends the commit phase. Only callable by the contract owner.
+  pub fn close_commits(&mut self) {
+    require(is_owner(&TRANSACTION.from));
+    require(self.phase == Phase::Commit);
+
+    self.phase = Phase::Reveal;
+
+    event!(CommitsClosed);
+  }
+
+  /// Reveals the caller's secret and returns the deposit.
+  pub fn reveal(&mut self, secret: U256) {
+    require(self.phase == Phase::Reveal);
+    require(!self.revealed.contains(&TRANSACTION.from));
+
+    require(self.commitments.get(&TRANSACTION.from) == Some(&sha256(&secret.0)));
+
+    self.accumulator = self.accumulator ^ secret;
+    self.revealed.push(TRANSACTION.from);
+
+    if self.deposit > 0 {
+      TRANSACTION.from.transfer(self.deposit);
+    }
+
+    event!(Revealed, &TRANSACTION.from, &secret);
+  }
+
+  /// Ends the reveal phase, applies the penalty to everyone that didn't reveal and fixes the seed.
+  /// Only callable by the contract owner.
+  pub fn finish(&mut self) -> U256 {
+    require(is_owner(&TRANSACTION.from));
+    require(self.phase == Phase::Reveal);
+
+    for account in self.non_revealers() {
+      match self.penalty {
+        NonRevealPenalty::Forfeit => (),
+        NonRevealPenalty::SendTo(beneficiary) => beneficiary.transfer(self.deposit),
+        NonRevealPenalty::Refund => account.transfer(self.deposit)
+      }
+
+      event!(RevealMissed, &account);
+    }
+
+    let mut hasher = Sha256::new();
+    hasher.update(&self.accumulator.0);
+    hasher.update(&random_u256().0);
+
+    let seed = hasher.finalize();
+
+    self.seed = Some(seed);
+    self.phase = Phase::Finished;
+
+    event!(SeedFinished, &seed);
+
+    seed
+  }
+
+  /// Seed of a finished round.
+  pub fn seed(&self) -> Option<U256> {
+    self.seed
+  }
+
+  /// Random stream for the finished round, e.g. `rng.range(0, participants.len() as u64)`.
+  pub fn rng(&self) -> Rng {
+    require(self.phase == Phase::Finished);
+    Rng::from_seed(self.seed.unwrap())
+  }
+
+  /// Participants that revealed, in reveal order.
+  pub fn participants(&self) -> &[Address] {
+    &self.revealed
+  }
+
+  /// Participants that committed but didn't reveal, in commit order.
+  pub fn non_revealers(&self) -> Vec<Address> {
+    self.committers.iter()
+      .filter(|account| !self.revealed.contains(account))
+      .copied()
+      .collect()
+  }
+
+  /// Starts a new round with the same deposit and penalty.
+  pub fn reset(&mut self) {
+    require(is_owner(&TRANSACTION.from));
+    require(self.phase == Phase::Finished);
+
+    *self = CommitReveal::new(self.deposit, self.penalty);
+  }
+}
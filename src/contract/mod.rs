@@ -1,28 +1,56 @@
-use super::{Address, NULL_ADDRESS};
+use core::{ops::Deref, ptr::addr_of};
 
-use lazy_static::lazy_static;
+use super::{Address, NULL_ADDRESS, __set_owner, __balance, __balance_of};
 
 #[repr(C, packed)]
 #[derive(Copy, Clone, PartialEq)]
 pub struct ContractData {
   pub address: Address,
   pub owner: Address,
+  #[deprecated(note = "snapshot taken before the call, use balance() instead")]
   pub balance: u64
 }
 
 #[no_mangle]
-static mut _CONTRACT: ContractData = ContractData {
+#[allow(deprecated)]
+pub(crate) static mut _CONTRACT: ContractData = ContractData {
   address: NULL_ADDRESS,
   owner: NULL_ADDRESS,
   balance: 0,
 };
 
-fn contract() -> &'static ContractData {
+/// Reads `_CONTRACT` on every access, so changes made during the call (like a new owner) are visible.
+pub struct ContractContext;
+
+pub static CONTRACT: ContractContext = ContractContext;
+
+impl Deref for ContractContext {
+  type Target = ContractData;
+
+  fn deref(&self) -> &ContractData {
+    unsafe {
+      &*addr_of!(_CONTRACT)
+    }
+  }
+}
+
+pub(crate) fn set_owner(owner: Address) {
   unsafe {
-    return &_CONTRACT;
+    __set_owner(&owner as *const Address);
+    _CONTRACT.owner = owner;
   }
 }
 
-lazy_static! {
-  pub static ref CONTRACT: &'static ContractData = contract();
+/// Current balance of the contract, including transfers made earlier in this call.
+pub fn balance() -> u64 {
+  unsafe {
+    __balance()
+  }
+}
+
+/// Current balance of `address`, including transfers made earlier in this call.
+pub fn balance_of(address: &Address) -> u64 {
+  unsafe {
+    __balance_of(address as *const Address)
+  }
 }
@@ -0,0 +1,76 @@
+use serde::{Serialize, Deserialize};
+use super::{Address, Kryo, TRANSACTION, require, append_event, publish_event, event};
+
+#[derive(Serialize, Deserialize, Copy, Clone, PartialEq, Eq, Debug)]
+pub enum EscrowState {
+  AwaitingDeposit,
+  Funded,
+  Released,
+  Refunded
+}
+
+/// KRYO held by the contract on behalf of two parties.
+///
+/// The payer deposits the agreed amount, after that either the payer or the arbiter can release
+/// it to the payee, and either the payee or the arbiter can refund it to the payer.
+#[derive(Serialize, Deserialize, Copy, Clone, PartialEq)]
+pub struct Escrow {
+  payer: Address,
+  payee: Address,
+  arbiter: Address,
+  amount: Kryo,
+  state: EscrowState
+}
+
+impl Escrow {
+  pub fn new(payer: Address, payee: Address, arbiter: Address, amount: Kryo) -> Escrow {
+    Escrow {
+      payer,
+      payee,
+      arbiter,
+      amount,
+      state: EscrowState::AwaitingDeposit
+    }
+  }
+
+  pub fn state(&self) -> EscrowState {
+    self.state
+  }
+
+  pub fn amount(&self) -> Kryo {
+    self.amount
+  }
+
+  /// Funds the escrow, the payer has to send exactly the agreed amount with the call.
+  pub fn deposit(&mut self) {
+    require(self.state == EscrowState::AwaitingDeposit);
+    require(TRANSACTION.from == self.payer);
+    require(TRANSACTION.amount() == self.amount);
+
+    self.state = EscrowState::Funded;
+
+    event!(EscrowDeposited, &self.payer, &self.amount);
+  }
+
+  /// Sends the deposit to the payee. Only callable by the payer or the arbiter.
+  pub fn release(&mut self) {
+    require(self.state == EscrowState::Funded);
+    require(TRANSACTION.from == self.payer || TRANSACTION.from == self.arbiter);
+
+    self.state = EscrowState::Released;
+    self.payee.transfer(self.amount);
+
+    event!(EscrowReleased, &self.payee, &self.amount);
+  }
+
+  /// Returns the deposit to the payer. Only callable by the payee or the arbiter.
+  pub fn refund(&mut self) {
+    require(self.state == EscrowState::Funded);
+    require(TRANSACTION.from == self.payee || TRANSACTION.from == self.arbiter);
+
+    self.state = EscrowState::Refunded;
+    self.payer.transfer(self.amount);
+
+    event!(EscrowRefunded, &self.payer, &self.amount);
+  }
+}
@@ -0,0 +1,96 @@
+use blake2::digest::consts::U32;
+use sha2::Digest;
+
+use super::U256;
+
+/// Incremental hashing, feed the input piece by piece with `update` instead of
+/// concatenating it into one buffer first.
+pub trait Hasher: Sized {
+  type Output;
+
+  fn new() -> Self;
+  fn update(&mut self, data: &[u8]);
+  fn finalize(self) -> Self::Output;
+
+  fn hash(data: &[u8]) -> Self::Output {
+    let mut hasher = Self::new();
+    hasher.update(data);
+    hasher.finalize()
+  }
+}
+
+pub struct Sha256(sha2::Sha256);
+pub struct Sha512(sha2::Sha512);
+pub struct Keccak256(sha3::Keccak256);
+pub struct Blake2b256(blake2::Blake2b<U32>);
+pub struct Blake3(blake3::Hasher);
+pub struct Ripemd160(ripemd::Ripemd160);
+
+// hashers backed by the RustCrypto `Digest` trait
+macro_rules! digest_hasher {
+  ($name:ident, $output:ty, $convert:expr) => {
+    impl Hasher for $name {
+      type Output = $output;
+
+      fn new() -> Self {
+        $name(Digest::new())
+      }
+
+      fn update(&mut self, data: &[u8]) {
+        Digest::update(&mut self.0, data);
+      }
+
+      fn finalize(self) -> Self::Output {
+        let digest = Digest::finalize(self.0);
+        $convert(digest.into())
+      }
+    }
+  };
+}
+
+digest_hasher!(Sha256, U256, U256);
+digest_hasher!(Sha512, [u8; 64], |bytes| bytes);
+digest_hasher!(Keccak256, U256, U256);
+digest_hasher!(Blake2b256, U256, U256);
+digest_hasher!(Ripemd160, [u8; 20], |bytes| bytes);
+
+impl Hasher for Blake3 {
+  type Output = U256;
+
+  fn new() -> Self {
+    Blake3(blake3::Hasher::new())
+  }
+
+  fn update(&mut self, data: &[u8]) {
+    self.0.update(data);
+  }
+
+  fn finalize(self) -> Self::Output {
+    U256(*self.0.finalize().as_bytes())
+  }
+}
+
+pub fn sha256(message: &[u8]) -> U256 {
+  Sha256::hash(message)
+}
+
+pub fn sha512(message: &[u8]) -> [u8; 64] {
+  Sha512::hash(message)
+}
+
+/// Keccak-256 as used by Ethereum (original padding, not the final SHA3-256 standard).
+pub fn keccak256(message: &[u8]) -> U256 {
+  Keccak256::hash(message)
+}
+
+pub fn blake2b256(message: &[u8]) -> U256 {
+  Blake2b256::hash(message)
+}
+
+pub fn blake3(message: &[u8]) -> U256 {
+  Blake3::hash(message)
+}
+
+pub fn ripemd160(message: &[u8]) -> [u8; 20] {
+  Ripemd160::hash(message)
+}
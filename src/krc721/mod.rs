@@ -1,3 +1,4 @@
+use alloc::{string::String, vec::Vec};
 use crate::{Address, U256, __transfer_token, __consume_token, __approval};
 
 pub trait KRC165 {
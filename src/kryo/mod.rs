@@ -0,0 +1,227 @@
+use alloc::{format, string::ToString};
+use core::{fmt, ops, str::FromStr};
+
+use serde::{Serialize, Deserialize, de::Visitor};
+use super::{PointerTrait, require};
+
+pub const KRYO_DECIMALS: u32 = 6;
+/// Smallest units in one KRYO.
+pub const KRYO: u64 = 1_000_000;
+
+/// Amount of the native token, counted in millionths of a KRYO.
+///
+/// Arithmetic operators revert the call on overflow, use the `checked_*` methods to handle it
+/// yourself. `100kryo` and `0.5kryo` literals inside `#[smart_contract]` and `#[interface]` impls
+/// are turned into `Kryo` values at compile time.
+#[repr(transparent)]
+#[derive(Copy, Clone, Default, Hash, Eq, PartialEq, Ord, PartialOrd)]
+pub struct Kryo(u64);
+
+impl Kryo {
+  pub const ZERO: Kryo = Kryo(0);
+  pub const MAX: Kryo = Kryo(u64::MAX);
+
+  pub const fn from_units(units: u64) -> Kryo {
+    Kryo(units)
+  }
+
+  pub const fn as_units(&self) -> u64 {
+    self.0
+  }
+
+  pub fn checked_add(self, rhs: Kryo) -> Option<Kryo> {
+    self.0.checked_add(rhs.0).map(Kryo)
+  }
+
+  pub fn checked_sub(self, rhs: Kryo) -> Option<Kryo> {
+    self.0.checked_sub(rhs.0).map(Kryo)
+  }
+
+  pub fn checked_mul(self, rhs: u64) -> Option<Kryo> {
+    self.0.checked_mul(rhs).map(Kryo)
+  }
+
+  pub fn checked_div(self, rhs: u64) -> Option<Kryo> {
+    self.0.checked_div(rhs).map(Kryo)
+  }
+
+  pub fn saturating_sub(self, rhs: Kryo) -> Kryo {
+    Kryo(self.0.saturating_sub(rhs.0))
+  }
+}
+
+fn or_revert(amount: Option<Kryo>) -> Kryo {
+  require(amount.is_some());
+  amount.unwrap()
+}
+
+impl ops::Add<Kryo> for Kryo {
+  type Output = Kryo;
+
+  fn add(self, rhs: Kryo) -> Kryo {
+    or_revert(self.checked_add(rhs))
+  }
+}
+
+impl ops::Sub<Kryo> for Kryo {
+  type Output = Kryo;
+
+  fn sub(self, rhs: Kryo) -> Kryo {
+    or_revert(self.checked_sub(rhs))
+  }
+}
+
+impl ops::Mul<u64> for Kryo {
+  type Output = Kryo;
+
+  fn mul(self, rhs: u64) -> Kryo {
+    or_revert(self.checked_mul(rhs))
+  }
+}
+
+impl ops::Div<u64> for Kryo {
+  type Output = Kryo;
+
+  fn div(self, rhs: u64) -> Kryo {
+    or_revert(self.checked_div(rhs))
+  }
+}
+
+impl ops::AddAssign<Kryo> for Kryo {
+  fn add_assign(&mut self, rhs: Kryo) {
+    *self = *self + rhs;
+  }
+}
+
+impl ops::SubAssign<Kryo> for Kryo {
+  fn sub_assign(&mut self, rhs: Kryo) {
+    *self = *self - rhs;
+  }
+}
+
+impl From<u64> for Kryo {
+  fn from(units: u64) -> Kryo {
+    Kryo(units)
+  }
+}
+
+impl From<Kryo> for u64 {
+  fn from(amount: Kryo) -> u64 {
+    amount.0
+  }
+}
+
+impl fmt::Display for Kryo {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}.{:06}", self.0 / KRYO, self.0 % KRYO)
+  }
+}
+
+// same as the literal, `1.500000kryo`
+impl fmt::Debug for Kryo {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}kryo", self)
+  }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum ParseKryoError {
+  Invalid,
+  TooPrecise,
+  Overflow
+}
+
+impl fmt::Display for ParseKryoError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      ParseKryoError::Invalid => write!(f, "invalid KRYO amount"),
+      ParseKryoError::TooPrecise => write!(f, "KRYO amount has more than {} decimals", KRYO_DECIMALS),
+      ParseKryoError::Overflow => write!(f, "KRYO amount too large")
+    }
+  }
+}
+
+impl FromStr for Kryo {
+  type Err = ParseKryoError;
+
+  /// Parses a decimal amount such as `12` or `0.000001`.
+  fn from_str(s: &str) -> Result<Kryo, ParseKryoError> {
+    let (whole, fraction) = s.split_once('.').unwrap_or((s, ""));
+
+    let is_digits = |part: &str| part.bytes().all(|b| b.is_ascii_digit());
+
+    if whole.is_empty() || !is_digits(whole) || !is_digits(fraction) {
+      return Err(ParseKryoError::Invalid);
+    }
+
+    let fraction = fraction.trim_end_matches('0');
+
+    if fraction.len() > KRYO_DECIMALS as usize {
+      return Err(ParseKryoError::TooPrecise);
+    }
+
+    let whole: u64 = whole.parse().map_err(|_| ParseKryoError::Overflow)?;
+    let fraction: u64 = format!("{:0<6}", fraction).parse().unwrap();
+
+    whole.checked_mul(KRYO)
+      .and_then(|units| units.checked_add(fraction))
+      .map(Kryo)
+      .ok_or(ParseKryoError::Overflow)
+  }
+}
+
+// events carry it like a plain u64
+impl PointerTrait for Kryo {
+  fn size(&self) -> usize {
+    self.0.size()
+  }
+
+  fn get_type(&self) -> &str {
+    self.0.get_type()
+  }
+
+  fn as_pointer(&self) -> *const u8 {
+    self.0.as_pointer()
+  }
+}
+
+impl Serialize for Kryo {
+  fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+  where
+    S: serde::Serializer {
+      if !serializer.is_human_readable() {
+        return serializer.serialize_u64(self.0);
+      }
+
+      serializer.serialize_str(&self.to_string())
+  }
+}
+
+impl<'de> Deserialize<'de> for Kryo {
+  fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+  where
+    D: serde::Deserializer<'de> {
+      if !deserializer.is_human_readable() {
+        return u64::deserialize(deserializer).map(Kryo);
+      }
+
+      deserializer.deserialize_str(StringVisitor)
+  }
+}
+
+struct StringVisitor;
+
+impl<'de> Visitor<'de> for StringVisitor {
+  type Value = Kryo;
+
+  fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+    write!(formatter, "a decimal string represents KRYO amount")
+  }
+
+  fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+  where
+    E: serde::de::Error,
+  {
+    v.parse().map_err(E::custom)
+  }
+}
@@ -1,24 +1,72 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+mod abi;
+mod access;
+mod allocator;
 mod address;
+mod block;
+mod call;
+mod collections;
+mod commit_reveal;
 mod contract;
+mod escrow;
+mod hash;
 mod transaction;
 mod krc721;
+mod kryo;
+mod manifest;
+mod merkle;
+mod multisig;
+mod pausable;
+mod random;
+mod reentrancy;
+mod signature;
+#[cfg(feature = "testing")]
+mod testing;
+mod timelock;
 mod u256;
+mod upgrade;
+mod vesting;
 
-extern crate wee_alloc;
+extern crate alloc;
 extern crate num_bigint;
 
-use std::{alloc::{GlobalAlloc, Layout}, mem::size_of, any::type_name};
+use core::{alloc::Layout, mem::size_of, any::type_name};
 
-use num_bigint::BigUint;
 pub use u256::*;
+pub use abi::*;
+pub use access::*;
+pub use allocator::*;
 pub use address::*;
+pub use block::*;
+pub use call::*;
+pub use collections::*;
+pub use commit_reveal::*;
 pub use contract::*;
+pub use escrow::*;
+pub use hash::*;
 pub use transaction::*;
 pub use kryolite_macro::*;
 pub use krc721::*;
+pub use kryo::*;
+pub use manifest::*;
+pub use merkle::*;
+pub use multisig::*;
+pub use pausable::*;
+pub use random::*;
+pub use reentrancy::*;
+pub use signature::*;
+#[cfg(feature = "testing")]
+pub use testing::*;
+pub use timelock::*;
+pub use upgrade::*;
+pub use vesting::*;
 pub use serde::*;
 pub use serde_json;
 
+// contracts are no_std as well, this brings the usual alloc types in with the rest of the SDK
+pub use alloc::{boxed::Box, borrow::ToOwned, string::{String, ToString}, vec::Vec, format, vec};
+
 use data_encoding::Encoding;
 use data_encoding_macro::new_encoding;
 
@@ -35,17 +83,13 @@ pub fn require(condition: bool) {
   }
 }
 
+#[deprecated(note = "only 24 bits of precision, use random_range() instead")]
 pub fn rand() -> f32 {
   unsafe {
-    return __rand();
+    __rand()
   }
 }
 
-pub fn sha256(message: &[u8]) -> U256 {
-  let digest = hashes::sha2::sha256::hash(message);
-  U256(digest.into_bytes())
-}
-
 pub trait Numeric {}
 impl Numeric for bool {}
 impl Numeric for f64 {}
@@ -67,7 +111,7 @@ pub trait PointerTrait {
   fn as_pointer(&self) -> *const u8;
 }
 
-impl<'a> PointerTrait for &'a str {
+impl PointerTrait for &str {
     fn size(&self) -> usize {
       self.len()
     }
@@ -95,7 +139,19 @@ impl PointerTrait for Address {
   }
 }
 
-// TODO: U256
+impl PointerTrait for U256 {
+  fn size(&self) -> usize {
+    self.len()
+  }
+
+  fn get_type(&self) -> &str {
+      "U256"
+  }
+
+  fn as_pointer(&self) -> *const u8 {
+    self.as_ptr()
+  }
+}
 
 impl<T: Numeric> PointerTrait for T {
   fn size(&self) -> usize {
@@ -141,14 +197,36 @@ pub fn push_return(val: &str) {
   }
 }
 
-// Use `wee_alloc` as the global allocator.
+// a panic can't unwind out of wasm, revert the call instead
+#[cfg(all(target_arch = "wasm32", not(feature = "std")))]
+#[panic_handler]
+fn panic(_info: &core::panic::PanicInfo) -> ! {
+  unsafe {
+    __exit(-1);
+  }
+
+  core::arch::wasm32::unreachable()
+}
+
+#[cfg(target_arch = "wasm32")]
 #[global_allocator]
-static ALLOC: wee_alloc::WeeAlloc = wee_alloc::WeeAlloc::INIT;
+static ALLOC: FreeListAllocator = FreeListAllocator::new();
 
+// replaced by the mocks in `testing` when contracts run natively
+#[cfg(not(feature = "testing"))]
 extern "C" {
   pub fn __exit(exitCode: i32);
   pub fn __rand() -> f32;
+  pub fn __random_seed(seed: *mut U256);
+  pub fn __remaining_gas() -> u64;
   pub fn __transfer(addr_ptr: *const Address, value: u64);
+  pub fn __call(addr_ptr: *const Address, method: *const u8, method_len: usize, params: *const u8, params_len: usize, value: u64) -> bool;
+  pub fn __balance() -> u64;
+  pub fn __balance_of(addr_ptr: *const Address) -> u64;
+  pub fn __set_owner(addr_ptr: *const Address);
+  pub fn __upgrade(code: *const u8, code_len: usize);
+  pub fn __verify_signature(public_key: *const u8, message: *const u8, message_len: usize, signature: *const u8) -> bool;
+  pub fn __public_key_to_address(public_key: *const u8, addr_ptr: *mut Address);
   pub fn __transfer_token(from: *const Address, to: *const Address, token_id: *const U256);
   pub fn __consume_token(owner: *const Address, token_id: *const U256);
   pub fn __approval(from: *const Address, to: *const Address, token_id: *const U256);
@@ -158,16 +236,38 @@ extern "C" {
   pub fn __return(str: *const u8, val_len: usize);
 }
 
+fn host_layout(len: usize, align: usize) -> Layout {
+  let layout = Layout::from_size_align(len, align);
+  require(layout.is_ok());
+  layout.unwrap()
+}
+
+/// # Safety
+/// Called by the host to reserve memory for values it writes into the contract. `align` has
+/// to be a power of two, reverts if it isn't or there is no memory left.
 #[no_mangle]
-pub unsafe fn __malloc(len: usize) -> *mut u8  {
-  let layout = Layout::from_size_align(len, 1);
-  ALLOC.alloc(layout.unwrap()) as *mut u8
+pub unsafe fn __malloc(len: usize, align: usize) -> *mut u8  {
+  let layout = host_layout(len, align);
+
+  // zero sized values don't need memory, any aligned address will do
+  if layout.size() == 0 {
+    return layout.align() as *mut u8;
+  }
+
+  let ptr = alloc::alloc::alloc(layout);
+  require(!ptr.is_null());
+  ptr
 }
 
+/// # Safety
+/// `ptr`, `len` and `align` must come from a previous `__malloc` call.
 #[no_mangle]
-pub unsafe fn __free(ptr: *mut u8, len: usize) {
-  let layout = Layout::from_size_align(len, 1);
-  ALLOC.dealloc(ptr, layout.unwrap())
+pub unsafe fn __free(ptr: *mut u8, len: usize, align: usize) {
+  let layout = host_layout(len, align);
+
+  if layout.size() > 0 {
+    alloc::alloc::dealloc(ptr, layout);
+  }
 }
 
 #[macro_export]
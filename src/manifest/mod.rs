@@ -0,0 +1,9 @@
+//! Contracts carry their manifest in the `kryolite_manifest` section of their code, so anyone
+//! holding the code can tell what it exports without a separate manifest.json.
+
+pub use kryolite_manifest::{Contract as Manifest, WasmError};
+
+/// Extracts the manifest from a contract's `.wasm` file.
+pub fn read_manifest(wasm: &[u8]) -> Result<Manifest, WasmError> {
+  Manifest::from_wasm(wasm)
+}
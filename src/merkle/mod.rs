@@ -0,0 +1,252 @@
+//! Merkle proofs over keccak256, laid out like OpenZeppelin's `StandardMerkleTree` so that
+//! roots and proofs produced by the usual airdrop tooling verify here as well.
+//!
+//! Sorted pair trees hash `keccak256(min(a, b) || max(a, b))` and don't need the position of
+//! a leaf to verify it. Indexed trees hash `keccak256(left || right)`, the leaf index picks the
+//! side at every level and the leaves are padded with zeroes to a power of two.
+
+use alloc::{collections::VecDeque, vec, vec::Vec};
+
+use super::{U256, Hasher, Keccak256, keccak256};
+
+/// Leaf hash for arbitrary data, hashed twice so that a leaf can never be mistaken for an inner node.
+pub fn hash_leaf(data: &[u8]) -> U256 {
+  keccak256(&keccak256(data).0)
+}
+
+pub fn hash_sorted_pair(a: &U256, b: &U256) -> U256 {
+  if a < b {
+    hash_pair(a, b)
+  } else {
+    hash_pair(b, a)
+  }
+}
+
+pub fn hash_pair(left: &U256, right: &U256) -> U256 {
+  let mut hasher = Keccak256::new();
+  hasher.update(&left.0);
+  hasher.update(&right.0);
+  hasher.finalize()
+}
+
+/// Verifies a proof of a sorted pair tree.
+pub fn verify_proof(root: U256, leaf: U256, proof: &[U256]) -> bool {
+  let computed = proof.iter().fold(leaf, |node, sibling| hash_sorted_pair(&node, sibling));
+  computed == root
+}
+
+/// Verifies a proof of an indexed tree, `index` is the position of the leaf.
+pub fn verify_indexed_proof(root: U256, leaf: U256, index: u64, proof: &[U256]) -> bool {
+  if proof.len() < 64 && index >> proof.len() != 0 {
+    return false;
+  }
+
+  let mut node = leaf;
+  let mut position = index;
+
+  for sibling in proof {
+    node = match position & 1 {
+      0 => hash_pair(&node, sibling),
+      _ => hash_pair(sibling, &node)
+    };
+
+    position >>= 1;
+  }
+
+  node == root
+}
+
+/// Verifies that all of `leaves` are part of a sorted pair tree at once.
+///
+/// `proof_flags` tells for every hashing step whether the second operand is the next leaf or
+/// computed hash (`true`) or the next `proof` element (`false`), as produced by
+/// [`MerkleTree::multiproof`].
+pub fn verify_multiproof(root: U256, leaves: &[U256], proof: &[U256], proof_flags: &[bool]) -> bool {
+  if leaves.len() + proof.len() != proof_flags.len() + 1 {
+    return false;
+  }
+
+  let mut hashes: Vec<U256> = Vec::with_capacity(proof_flags.len());
+  let mut leaf_pos = 0;
+  let mut hash_pos = 0;
+  let mut proof_pos = 0;
+
+  for flag in proof_flags {
+    let a = if leaf_pos < leaves.len() {
+      leaf_pos += 1;
+      leaves[leaf_pos - 1]
+    } else if hash_pos < hashes.len() {
+      hash_pos += 1;
+      hashes[hash_pos - 1]
+    } else {
+      return false;
+    };
+
+    let b = if *flag {
+      if leaf_pos < leaves.len() {
+        leaf_pos += 1;
+        leaves[leaf_pos - 1]
+      } else if hash_pos < hashes.len() {
+        hash_pos += 1;
+        hashes[hash_pos - 1]
+      } else {
+        return false;
+      }
+    } else {
+      if proof_pos >= proof.len() {
+        return false;
+      }
+
+      proof_pos += 1;
+      proof[proof_pos - 1]
+    };
+
+    hashes.push(hash_sorted_pair(&a, &b));
+  }
+
+  match (hashes.last(), leaves.first(), proof.first()) {
+    (Some(computed), _, _) => proof_pos == proof.len() && *computed == root,
+    (None, Some(leaf), _) => *leaf == root,
+    (None, None, Some(node)) => *node == root,
+    (None, None, None) => false
+  }
+}
+
+/// Proof for several leaves of a sorted pair tree, see [`verify_multiproof`].
+#[derive(Clone, PartialEq)]
+pub struct MultiProof {
+  pub leaves: Vec<U256>,
+  pub proof: Vec<U256>,
+  pub proof_flags: Vec<bool>
+}
+
+/// Off-chain tree builder producing the roots and proofs the verifiers above expect.
+///
+/// Nodes are stored as a binary heap: the root at 0 and the children of `i` at `2i + 1` and `2i + 2`.
+pub struct MerkleTree {
+  nodes: Vec<U256>,
+  leaf_count: usize,
+  indexed: bool
+}
+
+impl MerkleTree {
+  /// Sorted pair tree, leaves are stored in reverse order at the end of the heap. The leaves
+  /// are kept in the given order, `StandardMerkleTree.of` sorts them first, so sort the leaf
+  /// hashes to get its root.
+  pub fn new(leaves: &[U256]) -> MerkleTree {
+    assert!(!leaves.is_empty(), "merkle tree needs at least one leaf");
+
+    let mut nodes = vec![U256([0; 32]); 2 * leaves.len() - 1];
+    let count = nodes.len();
+
+    for (i, leaf) in leaves.iter().enumerate() {
+      nodes[count - 1 - i] = *leaf;
+    }
+
+    for i in (0..count - leaves.len()).rev() {
+      nodes[i] = hash_sorted_pair(&nodes[2 * i + 1], &nodes[2 * i + 2]);
+    }
+
+    MerkleTree { nodes, leaf_count: leaves.len(), indexed: false }
+  }
+
+  /// Indexed tree, leaves are padded with zeroes to a power of two and stored in order.
+  pub fn new_indexed(leaves: &[U256]) -> MerkleTree {
+    assert!(!leaves.is_empty(), "merkle tree needs at least one leaf");
+
+    let width = leaves.len().next_power_of_two();
+    let mut nodes = vec![U256([0; 32]); 2 * width - 1];
+
+    nodes[width - 1..width - 1 + leaves.len()].copy_from_slice(leaves);
+
+    for i in (0..width - 1).rev() {
+      nodes[i] = hash_pair(&nodes[2 * i + 1], &nodes[2 * i + 2]);
+    }
+
+    MerkleTree { nodes, leaf_count: leaves.len(), indexed: true }
+  }
+
+  pub fn root(&self) -> U256 {
+    self.nodes[0]
+  }
+
+  pub fn leaf_count(&self) -> usize {
+    self.leaf_count
+  }
+
+  /// Proof for the leaf at `index`, in the order the verifiers consume it.
+  pub fn proof(&self, index: usize) -> Vec<U256> {
+    assert!(index < self.leaf_count, "leaf index out of range");
+
+    let mut node = self.node_index(index);
+    let mut proof: Vec<U256> = Vec::new();
+
+    while node > 0 {
+      proof.push(self.nodes[sibling(node)]);
+      node = parent(node);
+    }
+
+    proof
+  }
+
+  /// Proof for several leaves of a sorted pair tree at once.
+  pub fn multiproof(&self, indices: &[usize]) -> MultiProof {
+    assert!(!self.indexed, "multiproofs need a sorted pair tree");
+
+    let mut nodes: Vec<usize> = indices.iter().map(|index| {
+      assert!(*index < self.leaf_count, "leaf index out of range");
+      self.node_index(*index)
+    }).collect();
+
+    nodes.sort_unstable_by(|a, b| b.cmp(a));
+    nodes.dedup();
+
+    let mut stack: VecDeque<usize> = nodes.iter().copied().collect();
+    let mut proof: Vec<U256> = Vec::new();
+    let mut proof_flags: Vec<bool> = Vec::new();
+
+    while let Some(node) = stack.pop_front().filter(|node| *node > 0) {
+      let sibling = sibling(node);
+
+      if stack.front() == Some(&sibling) {
+        proof_flags.push(true);
+        stack.pop_front();
+      } else {
+        proof_flags.push(false);
+        proof.push(self.nodes[sibling]);
+      }
+
+      stack.push_back(parent(node));
+    }
+
+    if nodes.is_empty() {
+      proof.push(self.root());
+    }
+
+    MultiProof {
+      leaves: nodes.iter().map(|node| self.nodes[*node]).collect(),
+      proof,
+      proof_flags
+    }
+  }
+
+  fn node_index(&self, index: usize) -> usize {
+    if self.indexed {
+      self.nodes.len() / 2 + index
+    } else {
+      self.nodes.len() - 1 - index
+    }
+  }
+}
+
+fn sibling(node: usize) -> usize {
+  if node % 2 == 1 {
+    node + 1
+  } else {
+    node - 1
+  }
+}
+
+fn parent(node: usize) -> usize {
+  (node - 1) / 2
+}
@@ -0,0 +1,183 @@
+use alloc::{string::String, vec, vec::Vec};
+use serde::{Serialize, Deserialize};
+use super::{Address, Kryo, TRANSACTION, require, append_event, publish_event, event};
+
+/// What a proposal does once enough owners have confirmed it.
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+pub enum Action {
+  Transfer { to: Address, amount: Kryo },
+  /// See `Address::call` for the format of `params`.
+  Call { contract: Address, method: String, params: String, amount: Kryo },
+  AddOwner(Address),
+  RemoveOwner(Address),
+  ChangeThreshold(u32)
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+pub struct Proposal {
+  pub id: u64,
+  pub proposer: Address,
+  pub action: Action,
+  pub confirmations: Vec<Address>,
+  pub executed: bool
+}
+
+/// M-of-N approval for moving funds, as a replacement for a single owner key.
+///
+/// Any owner can `submit` a proposal, which counts as their confirmation. The proposal executes
+/// as soon as `threshold` current owners have confirmed it. Owners and the threshold can only be
+/// changed through proposals as well, so no single key controls the component.
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+pub struct Multisig {
+  owners: Vec<Address>,
+  threshold: u32,
+  proposals: Vec<Proposal>
+}
+
+impl Multisig {
+  pub fn new(owners: Vec<Address>, threshold: u32) -> Multisig {
+    require(threshold > 0 && threshold as usize <= owners.len());
+
+    for (i, owner) in owners.iter().enumerate() {
+      require(!owners[..i].contains(owner));
+    }
+
+    Multisig {
+      owners,
+      threshold,
+      proposals: Vec::new()
+    }
+  }
+
+  pub fn owners(&self) -> &[Address] {
+    &self.owners
+  }
+
+  pub fn is_owner(&self, account: &Address) -> bool {
+    self.owners.contains(account)
+  }
+
+  pub fn threshold(&self) -> u32 {
+    self.threshold
+  }
+
+  pub fn proposal(&self, id: u64) -> Option<&Proposal> {
+    self.proposals.get(id as usize)
+  }
+
+  /// Proposals that haven't been executed yet, oldest first.
+  pub fn pending(&self) -> Vec<&Proposal> {
+    self.proposals.iter().filter(|proposal| !proposal.executed).collect()
+  }
+
+  /// Confirmations of the proposal that still come from owners.
+  pub fn confirmations(&self, id: u64) -> u32 {
+    match self.proposal(id) {
+      Some(proposal) => proposal.confirmations.iter().filter(|owner| self.is_owner(owner)).count() as u32,
+      None => 0
+    }
+  }
+
+  /// Creates a proposal confirmed by the caller and returns its id. Only callable by an owner.
+  pub fn submit(&mut self, action: Action) -> u64 {
+    require(self.is_owner(&TRANSACTION.from));
+
+    let id = self.proposals.len() as u64;
+
+    self.proposals.push(Proposal {
+      id,
+      proposer: TRANSACTION.from,
+      action,
+      confirmations: vec![TRANSACTION.from],
+      executed: false
+    });
+
+    event!(ProposalSubmitted, &id, &TRANSACTION.from);
+
+    self.execute_if_confirmed(id);
+
+    id
+  }
+
+  /// Adds the caller's confirmation, executing the proposal once the threshold is met.
+  /// Only callable by an owner.
+  pub fn confirm(&mut self, id: u64) {
+    require(self.is_owner(&TRANSACTION.from));
+
+    let proposal = self.pending_proposal(id);
+    require(!proposal.confirmations.contains(&TRANSACTION.from));
+
+    proposal.confirmations.push(TRANSACTION.from);
+
+    event!(ProposalConfirmed, &id, &TRANSACTION.from);
+
+    self.execute_if_confirmed(id);
+  }
+
+  /// Takes back the caller's confirmation of a pending proposal.
+  pub fn revoke(&mut self, id: u64) {
+    let proposal = self.pending_proposal(id);
+    let pos = proposal.confirmations.iter().position(|owner| *owner == TRANSACTION.from);
+
+    require(pos.is_some());
+    proposal.confirmations.remove(pos.unwrap());
+
+    event!(ConfirmationRevoked, &id, &TRANSACTION.from);
+  }
+
+  /// Executes a proposal that has enough confirmations, e.g. after the threshold was lowered
+  /// or a failed call should be retried. Only callable by an owner.
+  pub fn execute(&mut self, id: u64) {
+    require(self.is_owner(&TRANSACTION.from));
+    require(self.confirmations(id) >= self.threshold);
+
+    let proposal = self.pending_proposal(id);
+    proposal.executed = true;
+
+    let action = proposal.action.clone();
+
+    match action {
+      Action::Transfer { to, amount } => to.transfer(amount),
+      Action::Call { contract, method, params, amount } => require(contract.call(&method, &params, amount)),
+      Action::AddOwner(owner) => {
+        require(!self.is_owner(&owner));
+
+        self.owners.push(owner);
+
+        event!(OwnerAdded, &owner);
+      },
+      Action::RemoveOwner(owner) => {
+        let pos = self.owners.iter().position(|account| *account == owner);
+
+        require(pos.is_some());
+        require(self.owners.len() > self.threshold as usize);
+
+        self.owners.remove(pos.unwrap());
+
+        event!(OwnerRemoved, &owner);
+      },
+      Action::ChangeThreshold(threshold) => {
+        require(threshold > 0 && threshold as usize <= self.owners.len());
+
+        self.threshold = threshold;
+
+        event!(ThresholdChanged, &threshold);
+      }
+    }
+
+    event!(ProposalExecuted, &id);
+  }
+
+  fn execute_if_confirmed(&mut self, id: u64) {
+    if self.confirmations(id) >= self.threshold {
+      self.execute(id);
+    }
+  }
+
+  fn pending_proposal(&mut self, id: u64) -> &mut Proposal {
+    let proposal = self.proposals.get_mut(id as usize);
+
+    require(proposal.as_ref().is_some_and(|proposal| !proposal.executed));
+    proposal.unwrap()
+  }
+}
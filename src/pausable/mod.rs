@@ -0,0 +1,41 @@
+use serde::{Serialize, Deserialize};
+use super::{TRANSACTION, require, is_owner, append_event, publish_event, event};
+
+/// Emergency stop switch, stored as a field named `pausable` in the contract state
+/// so that `#[when_not_paused]` and `#[when_paused]` methods can find it.
+#[derive(Serialize, Deserialize, Copy, Clone, Default, PartialEq)]
+pub struct Pausable {
+  paused: bool
+}
+
+impl Pausable {
+  pub fn new() -> Pausable {
+    Pausable {
+      paused: false
+    }
+  }
+
+  pub fn is_paused(&self) -> bool {
+    self.paused
+  }
+
+  /// Stops every `#[when_not_paused]` method. Only callable by the contract owner.
+  pub fn pause(&mut self) {
+    require(is_owner(&TRANSACTION.from));
+    require(!self.paused);
+
+    self.paused = true;
+
+    event!(Paused, &TRANSACTION.from);
+  }
+
+  /// Resumes normal operation. Only callable by the contract owner.
+  pub fn unpause(&mut self) {
+    require(is_owner(&TRANSACTION.from));
+    require(self.paused);
+
+    self.paused = false;
+
+    event!(Unpaused, &TRANSACTION.from);
+  }
+}
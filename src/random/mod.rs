@@ -0,0 +1,116 @@
+//! Randomness for contracts.
+//!
+//! Every value is derived from a 32 byte seed that the node writes through `__random_seed`.
+//! The node uses the VRF output of the block producer for the block that includes the
+//! transaction, hashed together with the transaction id, so each transaction in a block gets
+//! its own seed. Draws within one call are `sha256(seed || counter)` for an increasing counter.
+//!
+//! The block producer learns the VRF output before anyone else and can decide not to publish
+//! the block, so a draw that protects a lot of value should also mix in entropy that
+//! participants committed to beforehand, see [`CommitReveal`](crate::CommitReveal).
+
+use core::ptr::addr_of_mut;
+
+use super::{U256, Hasher, Sha256, require, __random_seed};
+
+/// Deterministic stream of random values derived from a seed.
+#[derive(Clone, PartialEq)]
+pub struct Rng {
+  seed: U256,
+  counter: u64
+}
+
+impl Rng {
+  pub fn from_seed(seed: U256) -> Rng {
+    Rng {
+      seed,
+      counter: 0
+    }
+  }
+
+  pub fn next_u256(&mut self) -> U256 {
+    self.counter += 1;
+
+    let mut hasher = Sha256::new();
+    hasher.update(&self.seed.0);
+    hasher.update(&self.counter.to_le_bytes());
+    hasher.finalize()
+  }
+
+  pub fn next_u64(&mut self) -> u64 {
+    let bytes = self.next_u256().0;
+    u64::from_le_bytes(bytes[0..8].try_into().unwrap())
+  }
+
+  /// Uniformly distributed value in `lo..hi`. Draws that would bias the result towards
+  /// the low end of the range are rejected and drawn again.
+  pub fn range(&mut self, lo: u64, hi: u64) -> u64 {
+    require(lo < hi);
+
+    let span = hi - lo;
+    // 2^64 % span, values below it would be picked once more than the rest
+    let threshold = span.wrapping_neg() % span;
+
+    loop {
+      let value = self.next_u64();
+
+      if value >= threshold {
+        return lo + value % span;
+      }
+    }
+  }
+
+  pub fn choose<'a, T>(&mut self, items: &'a [T]) -> Option<&'a T> {
+    if items.is_empty() {
+      return None;
+    }
+
+    let index = self.range(0, items.len() as u64) as usize;
+    items.get(index)
+  }
+
+  /// Fisher-Yates shuffle.
+  pub fn shuffle<T>(&mut self, items: &mut [T]) {
+    for i in (1..items.len()).rev() {
+      let j = self.range(0, i as u64 + 1) as usize;
+      items.swap(i, j);
+    }
+  }
+}
+
+pub(crate) static mut RNG: Option<Rng> = None;
+
+fn host_rng() -> &'static mut Rng {
+  unsafe {
+    let rng = &mut *addr_of_mut!(RNG);
+
+    rng.get_or_insert_with(|| {
+      let mut seed = U256([0; 32]);
+      __random_seed(&mut seed as *mut U256);
+
+      Rng::from_seed(seed)
+    })
+  }
+}
+
+/// Next 256 random bits of this call.
+pub fn random_u256() -> U256 {
+  host_rng().next_u256()
+}
+
+pub fn random_u64() -> u64 {
+  host_rng().next_u64()
+}
+
+/// Uniformly distributed value in `lo..hi`, see [`Rng::range`].
+pub fn random_range(lo: u64, hi: u64) -> u64 {
+  host_rng().range(lo, hi)
+}
+
+pub fn choose<T>(items: &[T]) -> Option<&T> {
+  host_rng().choose(items)
+}
+
+pub fn shuffle<T>(items: &mut [T]) {
+  host_rng().shuffle(items)
+}
@@ -0,0 +1,34 @@
+use super::require;
+
+pub(crate) static mut ENTERED: bool = false;
+
+/// Held for the duration of a `#[non_reentrant]` call. Entering a second guard while
+/// one is alive (for example when an outbound call re-enters an exported method) reverts.
+pub struct ReentrancyGuard {
+  _private: ()
+}
+
+impl ReentrancyGuard {
+  pub fn enter() -> ReentrancyGuard {
+    unsafe {
+      require(!ENTERED);
+      ENTERED = true;
+    }
+
+    ReentrancyGuard { _private: () }
+  }
+
+  pub fn is_entered() -> bool {
+    unsafe {
+      ENTERED
+    }
+  }
+}
+
+impl Drop for ReentrancyGuard {
+  fn drop(&mut self) {
+    unsafe {
+      ENTERED = false;
+    }
+  }
+}
@@ -0,0 +1,99 @@
+use super::Address;
+
+/// Ed25519 public key.
+#[repr(C)]
+#[derive(Copy, Clone, Hash, Eq, PartialEq)]
+pub struct PublicKey(pub [u8; 32]);
+
+/// Ed25519 signature.
+#[repr(C)]
+#[derive(Copy, Clone, Hash, Eq, PartialEq)]
+pub struct Signature(pub [u8; 64]);
+
+impl PublicKey {
+  /// Wallet address owned by this key.
+  ///
+  /// On wasm the node derives it. Native builds use a mock of the derivation that hasn't been
+  /// checked against an address the node produced, so tests shouldn't rely on it matching a
+  /// real wallet address.
+  pub fn to_address(&self) -> Address {
+    imp::to_address(self)
+  }
+
+  pub fn as_ptr(&self) -> *const u8 {
+    self.0.as_ptr()
+  }
+}
+
+impl Signature {
+  pub fn as_ptr(&self) -> *const u8 {
+    self.0.as_ptr()
+  }
+}
+
+/// Checks that `signature` was made over `message` by the holder of `public_key`.
+pub fn verify_signature(public_key: &PublicKey, message: &[u8], signature: &Signature) -> bool {
+  imp::verify_signature(public_key, message, signature)
+}
+
+#[cfg(target_arch = "wasm32")]
+mod imp {
+  use super::{Address, PublicKey, Signature};
+  use crate::{NULL_ADDRESS, __verify_signature, __public_key_to_address};
+
+  pub fn verify_signature(public_key: &PublicKey, message: &[u8], signature: &Signature) -> bool {
+    unsafe {
+      __verify_signature(public_key.as_ptr(), message.as_ptr(), message.len(), signature.as_ptr())
+    }
+  }
+
+  pub fn to_address(public_key: &PublicKey) -> Address {
+    let mut address = NULL_ADDRESS;
+
+    unsafe {
+      __public_key_to_address(public_key.as_ptr(), &mut address as *mut Address);
+    }
+
+    address
+  }
+}
+
+// native builds (tests, tooling) have no host. Signatures are plain ed25519, the address is a
+// mock of the node's derivation written from its address layout, there is no node vector for it
+#[cfg(not(target_arch = "wasm32"))]
+mod imp {
+  use alloc::vec;
+  use super::{Address, PublicKey, Signature};
+  use ed25519_dalek::{Verifier, VerifyingKey};
+  use ripemd::Ripemd160;
+  use sha2::{Digest, Sha256};
+
+  const ADDRESS_PREFIX: &str = "kryo:";
+  const MAIN_NETWORK: u8 = 0;
+  const WALLET_ADDRESS: u8 = 0;
+
+  pub fn verify_signature(public_key: &PublicKey, message: &[u8], signature: &Signature) -> bool {
+    let key = match VerifyingKey::from_bytes(&public_key.0) {
+      Ok(key) => key,
+      Err(_) => return false
+    };
+
+    key.verify(message, &ed25519_dalek::Signature::from_bytes(&signature.0)).is_ok()
+  }
+
+  // network | type | ripemd160(sha256(key)) | first 4 bytes of sha256(sha256(prefix | ...))
+  pub fn to_address(public_key: &PublicKey) -> Address {
+    let hash = Ripemd160::digest(Sha256::digest(public_key.0));
+
+    let mut bytes = vec![MAIN_NETWORK, WALLET_ADDRESS];
+    bytes.extend_from_slice(&hash);
+
+    let mut prefixed = ADDRESS_PREFIX.as_bytes().to_vec();
+    prefixed.extend_from_slice(&bytes);
+
+    let checksum = Sha256::digest(Sha256::digest(&prefixed));
+    bytes.extend_from_slice(&checksum[0..4]);
+
+    Address(bytes.try_into().unwrap())
+  }
+}
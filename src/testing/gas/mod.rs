@@ -0,0 +1,154 @@
+use std::{collections::BTreeMap, fmt};
+
+/// Gas charged for entering a call and for every host import.
+///
+/// Contracts run natively in tests, so wasm instructions can't be counted here and the gas
+/// reported is the host call cost only. The node doesn't publish a cost table, the values of
+/// `ESTIMATE` are rough weights of the imports against each other, not what a transaction
+/// costs on chain. Set the node's values with `TestEnv::set_gas_table` to get closer.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct GasTable {
+  /// Entering an exported method.
+  pub call: u64,
+  pub transfer: u64,
+  pub call_contract: u64,
+  pub call_contract_byte: u64,
+  pub balance: u64,
+  pub set_owner: u64,
+  pub upgrade: u64,
+  pub upgrade_byte: u64,
+  pub random_seed: u64,
+  pub rand: u64,
+  pub remaining_gas: u64,
+  /// `__transfer_token`, `__consume_token` and `__approval`.
+  pub token: u64,
+  pub println: u64,
+  pub append_event: u64,
+  pub event_byte: u64,
+  pub publish_event: u64,
+  pub return_byte: u64
+}
+
+impl GasTable {
+  pub const ESTIMATE: GasTable = GasTable {
+    call: 10_000,
+    transfer: 5_000,
+    call_contract: 25_000,
+    call_contract_byte: 10,
+    balance: 400,
+    set_owner: 5_000,
+    upgrade: 100_000,
+    upgrade_byte: 20,
+    random_seed: 1_000,
+    rand: 500,
+    remaining_gas: 100,
+    token: 5_000,
+    println: 0,
+    append_event: 200,
+    event_byte: 8,
+    publish_event: 1_000,
+    return_byte: 4
+  };
+}
+
+impl Default for GasTable {
+  fn default() -> GasTable {
+    GasTable::ESTIMATE
+  }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct MethodGas {
+  pub calls: u64,
+  pub reverted: u64,
+  pub min: u64,
+  pub max: u64,
+  pub total: u64
+}
+
+impl MethodGas {
+  pub fn average(&self) -> u64 {
+    self.total / self.calls.max(1)
+  }
+}
+
+/// Host call gas used per method over the calls made through a `TestEnv`, sorted by method
+/// name. Instructions executed by the method itself are not included.
+#[derive(Clone, Default, PartialEq, Eq, Debug)]
+pub struct GasReport {
+  methods: BTreeMap<String, MethodGas>
+}
+
+impl GasReport {
+  pub(crate) fn record(&mut self, method: &str, gas: u64, reverted: bool) {
+    let entry = self.methods.entry(method.into()).or_insert(MethodGas {
+      calls: 0,
+      reverted: 0,
+      min: u64::MAX,
+      max: 0,
+      total: 0
+    });
+
+    entry.calls += 1;
+    entry.reverted += reverted as u64;
+    entry.min = entry.min.min(gas);
+    entry.max = entry.max.max(gas);
+    entry.total += gas;
+  }
+
+  pub fn method(&self, name: &str) -> Option<&MethodGas> {
+    self.methods.get(name)
+  }
+
+  pub fn methods(&self) -> impl Iterator<Item = (&str, &MethodGas)> {
+    self.methods.iter().map(|(name, gas)| (name.as_str(), gas))
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.methods.is_empty()
+  }
+}
+
+/// One line per method, e.g. `buy_ticket: 41_203 host gas`, with the range when calls differ.
+impl fmt::Display for GasReport {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    for (name, gas) in &self.methods {
+      write!(f, "{}: {} host gas", name, Separated(gas.average()))?;
+
+      if gas.min != gas.max {
+        write!(f, " ({} - {})", Separated(gas.min), Separated(gas.max))?;
+      }
+
+      if gas.calls > 1 {
+        write!(f, ", {} calls", gas.calls)?;
+      }
+
+      if gas.reverted > 0 {
+        write!(f, ", {} reverted", gas.reverted)?;
+      }
+
+      writeln!(f)?;
+    }
+
+    Ok(())
+  }
+}
+
+// 41203 -> 41_203
+struct Separated(u64);
+
+impl fmt::Display for Separated {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let digits = self.0.to_string();
+
+    for (i, digit) in digits.chars().enumerate() {
+      if i > 0 && (digits.len() - i).is_multiple_of(3) {
+        write!(f, "_")?;
+      }
+
+      write!(f, "{}", digit)?;
+    }
+
+    Ok(())
+  }
+}
@@ -0,0 +1,244 @@
+//! Native stand-ins for the host imports, backed by the chain state of the active `TestEnv`.
+
+// same contract as the imports they replace
+#![allow(clippy::missing_safety_doc)]
+
+use std::{collections::BTreeMap, panic::panic_any, slice, str, sync::{Mutex, PoisonError}, vec::Vec};
+
+use crate::{Address, U256, _CONTRACT, sha256};
+use super::{GasTable, Revert, Event, EventParam, TokenOp, ContractCall, OUT_OF_GAS, INSUFFICIENT_BALANCE};
+
+#[derive(Clone)]
+pub(crate) struct Host {
+  pub gas_table: GasTable,
+  pub gas_limit: u64,
+  pub gas_used: u64,
+  pub contract: Address,
+  pub balances: BTreeMap<Address, u64>,
+  pub random_seed: U256,
+  pub rand_draws: u64,
+  pub event: Vec<EventParam>,
+  pub events: Vec<Event>,
+  pub returned: Vec<u8>,
+  pub token_ops: Vec<TokenOp>,
+  pub calls: Vec<ContractCall>,
+  pub call_result: bool,
+  pub upgraded_code: Option<Vec<u8>>
+}
+
+impl Host {
+  pub fn new(contract: Address) -> Host {
+    Host {
+      gas_table: GasTable::ESTIMATE,
+      gas_limit: 10_000_000,
+      gas_used: 0,
+      contract,
+      balances: BTreeMap::new(),
+      random_seed: U256([0; 32]),
+      rand_draws: 0,
+      event: Vec::new(),
+      events: Vec::new(),
+      returned: Vec::new(),
+      token_ops: Vec::new(),
+      calls: Vec::new(),
+      call_result: true,
+      upgraded_code: None
+    }
+  }
+
+  pub fn balance(&self, address: &Address) -> u64 {
+    self.balances.get(address).copied().unwrap_or(0)
+  }
+
+  // moves KRYO between accounts, false if `from` can't cover it
+  pub fn move_balance(&mut self, from: &Address, to: &Address, value: u64) -> bool {
+    if self.balance(from) < value {
+      return false;
+    }
+
+    *self.balances.entry(*from).or_default() -= value;
+    *self.balances.entry(*to).or_default() += value;
+    true
+  }
+}
+
+static HOST: Mutex<Option<Host>> = Mutex::new(None);
+
+pub(crate) fn with_host<R>(f: impl FnOnce(&mut Host) -> R) -> R {
+  let mut host = HOST.lock().unwrap_or_else(PoisonError::into_inner);
+  f(host.as_mut().expect("host imports are only available while a TestEnv is alive"))
+}
+
+pub(crate) fn set_host(host: Option<Host>) {
+  *HOST.lock().unwrap_or_else(PoisonError::into_inner) = host;
+}
+
+fn revert(code: i32) -> ! {
+  panic_any(Revert { code })
+}
+
+// gas is checked before the import does anything, like on the node
+fn charge(gas: u64) {
+  let out_of_gas = with_host(|host| {
+    host.gas_used = host.gas_used.saturating_add(gas);
+    host.gas_used > host.gas_limit
+  });
+
+  if out_of_gas {
+    revert(OUT_OF_GAS);
+  }
+}
+
+fn gas(cost: impl FnOnce(&GasTable) -> u64) -> u64 {
+  with_host(|host| cost(&host.gas_table))
+}
+
+unsafe fn bytes<'a>(ptr: *const u8, len: usize) -> &'a [u8] {
+  match len {
+    0 => &[],
+    _ => slice::from_raw_parts(ptr, len)
+  }
+}
+
+unsafe fn text<'a>(ptr: *const u8, len: usize) -> &'a str {
+  str::from_utf8(bytes(ptr, len)).unwrap_or("<invalid utf-8>")
+}
+
+pub unsafe fn __exit(exit_code: i32) {
+  revert(exit_code)
+}
+
+pub unsafe fn __rand() -> f32 {
+  charge(gas(|table| table.rand));
+
+  let draw = with_host(|host| {
+    host.rand_draws += 1;
+
+    let mut input = host.random_seed.0.to_vec();
+    input.extend_from_slice(&host.rand_draws.to_le_bytes());
+    sha256(&input)
+  });
+
+  (u32::from_le_bytes(draw.0[0..4].try_into().unwrap()) >> 8) as f32 / (1 << 24) as f32
+}
+
+pub unsafe fn __random_seed(seed: *mut U256) {
+  charge(gas(|table| table.random_seed));
+  *seed = with_host(|host| host.random_seed);
+}
+
+pub unsafe fn __remaining_gas() -> u64 {
+  charge(gas(|table| table.remaining_gas));
+  with_host(|host| host.gas_limit.saturating_sub(host.gas_used))
+}
+
+pub unsafe fn __transfer(addr_ptr: *const Address, value: u64) {
+  charge(gas(|table| table.transfer));
+
+  let to = *addr_ptr;
+
+  if !with_host(|host| host.move_balance(&host.contract.clone(), &to, value)) {
+    revert(INSUFFICIENT_BALANCE);
+  }
+}
+
+pub unsafe fn __call(addr_ptr: *const Address, method: *const u8, method_len: usize, params: *const u8, params_len: usize, value: u64) -> bool {
+  charge(gas(|table| table.call_contract + table.call_contract_byte * (method_len + params_len) as u64));
+
+  let call = ContractCall {
+    contract: *addr_ptr,
+    method: text(method, method_len).into(),
+    params: text(params, params_len).into(),
+    value: value.into()
+  };
+
+  with_host(|host| {
+    // a failed call keeps nothing, including the value sent with it
+    if !host.call_result || !host.move_balance(&host.contract.clone(), &call.contract, value) {
+      return false;
+    }
+
+    host.calls.push(call);
+    true
+  })
+}
+
+pub unsafe fn __balance() -> u64 {
+  charge(gas(|table| table.balance));
+  with_host(|host| host.balance(&host.contract))
+}
+
+pub unsafe fn __balance_of(addr_ptr: *const Address) -> u64 {
+  charge(gas(|table| table.balance));
+  with_host(|host| host.balance(&*addr_ptr))
+}
+
+pub unsafe fn __set_owner(addr_ptr: *const Address) {
+  charge(gas(|table| table.set_owner));
+  _CONTRACT.owner = *addr_ptr;
+}
+
+pub unsafe fn __upgrade(code: *const u8, code_len: usize) {
+  charge(gas(|table| table.upgrade + table.upgrade_byte * code_len as u64));
+
+  let code = bytes(code, code_len).to_vec();
+  with_host(|host| host.upgraded_code = Some(code));
+}
+
+pub unsafe fn __transfer_token(from: *const Address, to: *const Address, token_id: *const U256) {
+  charge(gas(|table| table.token));
+
+  let op = TokenOp::Transfer { from: *from, to: *to, token_id: *token_id };
+  with_host(|host| host.token_ops.push(op));
+}
+
+pub unsafe fn __consume_token(owner: *const Address, token_id: *const U256) {
+  charge(gas(|table| table.token));
+
+  let op = TokenOp::Consume { owner: *owner, token_id: *token_id };
+  with_host(|host| host.token_ops.push(op));
+}
+
+pub unsafe fn __approval(from: *const Address, to: *const Address, token_id: *const U256) {
+  charge(gas(|table| table.token));
+
+  let op = TokenOp::Approval { from: *from, to: *to, token_id: *token_id };
+  with_host(|host| host.token_ops.push(op));
+}
+
+pub unsafe fn __println(typ: *const u8, type_len: usize, val: *const u8, val_len: usize) {
+  charge(gas(|table| table.println));
+
+  let param = EventParam::new(text(typ, type_len), bytes(val, val_len));
+  std::println!("{}", param);
+}
+
+pub unsafe fn __append_event(typ: *const u8, type_len: usize, val: *const u8, val_len: usize) {
+  charge(gas(|table| table.append_event + table.event_byte * val_len as u64));
+
+  let param = EventParam::new(text(typ, type_len), bytes(val, val_len));
+  with_host(|host| host.event.push(param));
+}
+
+pub unsafe fn __publish_event() {
+  charge(gas(|table| table.publish_event));
+
+  with_host(|host| {
+    let mut params = std::mem::take(&mut host.event);
+
+    // the first value is the event name
+    let name = match params.is_empty() {
+      true => std::string::String::new(),
+      false => params.remove(0).as_str().unwrap_or_default().into()
+    };
+
+    host.events.push(Event { name, params });
+  });
+}
+
+pub unsafe fn __return(str: *const u8, val_len: usize) {
+  charge(gas(|table| table.return_byte * val_len as u64));
+
+  let value = bytes(str, val_len).to_vec();
+  with_host(|host| host.returned = value);
+}
@@ -0,0 +1,367 @@
+//! Runs contracts natively in `cargo test`, enabled with the `testing` feature.
+//!
+//! The host imports are replaced by mocks working on an in-memory chain: balances, events,
+//! token operations and outgoing calls can be inspected after every call, reverts roll all of
+//! it back, and every call is charged with the `GasTable`. The gas used per method is printed
+//! when the `TestEnv` is dropped (`cargo test -- --nocapture` to see it for passing tests), it
+//! only covers host calls since the contract's own instructions aren't metered natively.
+//!
+//! It isn't a model of the node's costs: the node doesn't publish its instruction and import
+//! costs, and native code has no wasm instructions to count. Use the report to catch a method
+//! making more host calls than before, not to predict the gas of a transaction.
+//!
+//! ```ignore
+//! let mut env = TestEnv::new();
+//! let mut lottery = env.deploy(KryoliteLottery::new);
+//!
+//! env.set_balance(test_account(1), Kryo::from_units(1_000_000_000));
+//! env.set_caller(test_account(1));
+//! env.set_value(lottery.ticket_price);
+//! env.call("buy_ticket", &mut lottery, |lottery| lottery.buy_ticket()).unwrap();
+//!
+//! assert_eq!(env.gas_report().method("buy_ticket").unwrap().calls, 1);
+//! ```
+
+mod gas;
+mod host;
+
+pub use gas::*;
+pub use host::{
+  __exit, __rand, __random_seed, __remaining_gas, __transfer, __call, __balance, __balance_of, __set_owner, __upgrade,
+  __transfer_token, __consume_token, __approval, __println, __append_event, __publish_event, __return
+};
+
+use std::{any::type_name, boxed::Box, fmt, panic::{self, AssertUnwindSafe}, string::String, sync::{Mutex, MutexGuard, Once, PoisonError}, vec::Vec};
+
+use host::{Host, with_host, set_host};
+use crate::{Address, Kryo, U256, B32, NULL_ADDRESS, ContractData, TransactionData, BlockData, _CONTRACT, _TRANSACTION, _BLOCK, DEPTH, ENTERED, reset_call_state};
+
+/// Exit code of a call that ran out of gas.
+pub const OUT_OF_GAS: i32 = -2;
+/// Exit code of a transfer the sender can't cover.
+pub const INSUFFICIENT_BALANCE: i32 = -3;
+
+/// A call ended with `__exit`, e.g. from a failed `require`.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct Revert {
+  pub code: i32
+}
+
+/// Value appended to an event, as the type name and raw bytes the contract passed.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct EventParam {
+  pub type_name: String,
+  pub bytes: Vec<u8>
+}
+
+impl EventParam {
+  pub(crate) fn new(type_name: &str, bytes: &[u8]) -> EventParam {
+    EventParam {
+      type_name: type_name.trim_start_matches('&').into(),
+      bytes: bytes.to_vec()
+    }
+  }
+
+  pub fn as_str(&self) -> Option<&str> {
+    core::str::from_utf8(&self.bytes).ok()
+  }
+
+  pub fn as_u64(&self) -> Option<u64> {
+    Some(u64::from_le_bytes(self.bytes.as_slice().try_into().ok()?))
+  }
+
+  pub fn as_address(&self) -> Option<Address> {
+    Some(Address(self.bytes.as_slice().try_into().ok()?))
+  }
+
+  pub fn as_u256(&self) -> Option<U256> {
+    Some(U256(self.bytes.as_slice().try_into().ok()?))
+  }
+}
+
+impl fmt::Display for EventParam {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self.type_name.as_str() {
+      "str" => write!(f, "{}", self.as_str().unwrap_or_default()),
+      "Address" => write!(f, "{}", self.as_address().map(|address| address.as_string()).unwrap_or_default()),
+      "U256" => write!(f, "{}", self.as_u256().map(|value| value.as_string()).unwrap_or_default()),
+      _ => match self.as_u64() {
+        Some(value) => write!(f, "{}", value),
+        None => write!(f, "{}({})", self.type_name, B32.encode(&self.bytes))
+      }
+    }
+  }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Event {
+  pub name: String,
+  pub params: Vec<EventParam>
+}
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub enum TokenOp {
+  Transfer { from: Address, to: Address, token_id: U256 },
+  Consume { owner: Address, token_id: U256 },
+  Approval { from: Address, to: Address, token_id: U256 }
+}
+
+/// Call made with `Address::call`.
+#[derive(Clone, PartialEq, Eq)]
+pub struct ContractCall {
+  pub contract: Address,
+  pub method: String,
+  pub params: String,
+  pub value: Kryo
+}
+
+/// Address of test account `n`, the contract is deployed by `test_account(0)`.
+pub fn test_account(n: u8) -> Address {
+  let mut bytes = [0; 26];
+  bytes[25] = n;
+  Address(bytes)
+}
+
+pub const TEST_CONTRACT: Address = Address([0xc0; 26]);
+
+// the context statics are shared by the whole process, so tests using them take turns
+static LOCK: Mutex<()> = Mutex::new(());
+static QUIET_REVERTS: Once = Once::new();
+
+/// Chain the contract under test runs on, see the module documentation.
+pub struct TestEnv {
+  caller: Address,
+  value: u64,
+  last_gas: u64,
+  report: GasReport,
+  _lock: MutexGuard<'static, ()>
+}
+
+impl TestEnv {
+  pub fn new() -> TestEnv {
+    // reverts are expected results here, not worth a panic message
+    QUIET_REVERTS.call_once(|| {
+      let default_hook = panic::take_hook();
+
+      panic::set_hook(Box::new(move |info| {
+        if info.payload().downcast_ref::<Revert>().is_none() {
+          default_hook(info);
+        }
+      }));
+    });
+
+    let lock = LOCK.lock().unwrap_or_else(PoisonError::into_inner);
+
+    set_host(Some(Host::new(TEST_CONTRACT)));
+
+    #[allow(deprecated)]
+    unsafe {
+      _CONTRACT = ContractData {
+        address: TEST_CONTRACT,
+        owner: test_account(0),
+        balance: 0
+      };
+
+      _TRANSACTION = TransactionData {
+        from: NULL_ADDRESS,
+        to: TEST_CONTRACT,
+        value: 0
+      };
+
+      _BLOCK = BlockData {
+        height: 1,
+        timestamp: 0,
+        parent_hash: U256([0; 32]),
+        chain_id: 0
+      };
+    }
+
+    TestEnv {
+      caller: test_account(0),
+      value: 0,
+      last_gas: 0,
+      report: GasReport::default(),
+      _lock: lock
+    }
+  }
+
+  /// Runs the constructor as the owner.
+  pub fn deploy<C>(&mut self, new: impl FnOnce() -> C) -> C {
+    let caller = self.caller;
+
+    self.caller = self.owner();
+    let contract = self.run("new", new);
+    self.caller = caller;
+
+    match contract {
+      Ok(contract) => contract,
+      Err(revert) => panic!("constructor of {} reverted with {}", type_name::<C>(), revert.code)
+    }
+  }
+
+  /// Calls a method as the current caller with the current value. On a revert the contract
+  /// and the chain are left as they were before the call.
+  pub fn call<C: Clone, R>(&mut self, method: &str, contract: &mut C, f: impl FnOnce(&mut C) -> R) -> Result<R, Revert> {
+    let backup = contract.clone();
+    let result = self.run(method, || f(contract));
+
+    if result.is_err() {
+      *contract = backup;
+    }
+
+    result
+  }
+
+  fn run<R>(&mut self, method: &str, f: impl FnOnce() -> R) -> Result<R, Revert> {
+    let snapshot = with_host(|host| host.clone());
+    let owner = self.owner();
+    let value = std::mem::take(&mut self.value);
+
+    unsafe {
+      _TRANSACTION = TransactionData {
+        from: self.caller,
+        to: TEST_CONTRACT,
+        value
+      };
+
+      // methods with a return value are called directly here, not through the exported
+      // wrapper that opens the call scope, so start every call the way the scope would
+      reset_call_state();
+      DEPTH = 0;
+      ENTERED = false;
+    }
+
+    let paid = with_host(|host| {
+      host.gas_used = host.gas_table.call;
+      host.returned.clear();
+      host.move_balance(&self.caller, &TEST_CONTRACT, value)
+    });
+
+    let result = match paid {
+      true => panic::catch_unwind(AssertUnwindSafe(f)).map_err(|payload| match payload.downcast::<Revert>() {
+        Ok(revert) => *revert,
+        Err(payload) => panic::resume_unwind(payload)
+      }),
+      false => Err(Revert { code: INSUFFICIENT_BALANCE })
+    };
+
+    self.last_gas = with_host(|host| host.gas_used);
+    self.report.record(method, self.last_gas, result.is_err());
+
+    if result.is_err() {
+      let gas_used = self.last_gas;
+
+      with_host(|host| {
+        *host = snapshot;
+        host.gas_used = gas_used;
+      });
+
+      unsafe {
+        _CONTRACT.owner = owner;
+      }
+    }
+
+    result
+  }
+
+  pub fn set_caller(&mut self, caller: Address) {
+    self.caller = caller;
+  }
+
+  /// KRYO sent with the next call only.
+  pub fn set_value(&mut self, value: Kryo) {
+    self.value = value.as_units();
+  }
+
+  pub fn set_block(&mut self, height: u64, timestamp: u64) {
+    unsafe {
+      _BLOCK.height = height;
+      _BLOCK.timestamp = timestamp;
+    }
+  }
+
+  pub fn set_balance(&mut self, address: Address, balance: Kryo) {
+    with_host(|host| host.balances.insert(address, balance.as_units()));
+  }
+
+  pub fn balance_of(&self, address: &Address) -> Kryo {
+    with_host(|host| host.balance(address)).into()
+  }
+
+  pub fn set_random_seed(&mut self, seed: U256) {
+    with_host(|host| host.random_seed = seed);
+  }
+
+  pub fn set_gas_table(&mut self, table: GasTable) {
+    with_host(|host| host.gas_table = table);
+  }
+
+  pub fn set_gas_limit(&mut self, limit: u64) {
+    with_host(|host| host.gas_limit = limit);
+  }
+
+  /// Whether calls made with `Address::call` succeed, `true` by default.
+  pub fn set_call_result(&mut self, success: bool) {
+    with_host(|host| host.call_result = success);
+  }
+
+  pub fn contract_address(&self) -> Address {
+    TEST_CONTRACT
+  }
+
+  pub fn owner(&self) -> Address {
+    unsafe {
+      _CONTRACT.owner
+    }
+  }
+
+  /// Events published so far, oldest first.
+  pub fn events(&self) -> Vec<Event> {
+    with_host(|host| host.events.clone())
+  }
+
+  pub fn take_events(&mut self) -> Vec<Event> {
+    with_host(|host| std::mem::take(&mut host.events))
+  }
+
+  pub fn token_ops(&self) -> Vec<TokenOp> {
+    with_host(|host| host.token_ops.clone())
+  }
+
+  pub fn contract_calls(&self) -> Vec<ContractCall> {
+    with_host(|host| host.calls.clone())
+  }
+
+  pub fn upgraded_code(&self) -> Option<Vec<u8>> {
+    with_host(|host| host.upgraded_code.clone())
+  }
+
+  /// Bytes handed to `__return` by the last call, e.g. by a `_json` export.
+  pub fn last_return(&self) -> Vec<u8> {
+    with_host(|host| host.returned.clone())
+  }
+
+  pub fn last_gas(&self) -> u64 {
+    self.last_gas
+  }
+
+  pub fn gas_report(&self) -> &GasReport {
+    &self.report
+  }
+}
+
+impl Default for TestEnv {
+  fn default() -> TestEnv {
+    TestEnv::new()
+  }
+}
+
+impl Drop for TestEnv {
+  fn drop(&mut self) {
+    if !self.report.is_empty() {
+      std::print!("gas report (host calls only)\n{}", self.report);
+    }
+
+    set_host(None);
+  }
+}
@@ -0,0 +1,79 @@
+use serde::{Serialize, Deserialize};
+use super::{Address, Kryo, BLOCK, TRANSACTION, require, append_event, publish_event, event};
+
+/// Point in time after which locked funds can be released.
+#[derive(Serialize, Deserialize, Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Unlock {
+  /// Block height, inclusive.
+  Height(u64),
+  /// Unix time in milliseconds, compared against `BLOCK.timestamp`.
+  Timestamp(u64)
+}
+
+impl Unlock {
+  pub fn is_reached(&self) -> bool {
+    match *self {
+      Unlock::Height(height) => BLOCK.height >= height,
+      Unlock::Timestamp(timestamp) => BLOCK.timestamp >= timestamp
+    }
+  }
+}
+
+/// KRYO locked for a beneficiary until a block height or timestamp.
+///
+/// Anyone can add funds with `lock()`, the beneficiary collects everything locked so far
+/// with `release()` once the unlock point has passed.
+#[derive(Serialize, Deserialize, Copy, Clone, PartialEq)]
+pub struct TimeLock {
+  beneficiary: Address,
+  unlock: Unlock,
+  locked: Kryo
+}
+
+impl TimeLock {
+  pub fn new(beneficiary: Address, unlock: Unlock) -> TimeLock {
+    TimeLock {
+      beneficiary,
+      unlock,
+      locked: Kryo::ZERO
+    }
+  }
+
+  pub fn beneficiary(&self) -> Address {
+    self.beneficiary
+  }
+
+  pub fn locked(&self) -> Kryo {
+    self.locked
+  }
+
+  pub fn is_unlocked(&self) -> bool {
+    self.unlock.is_reached()
+  }
+
+  /// Locks the KRYO sent with the call.
+  pub fn lock(&mut self) {
+    require(!self.is_unlocked());
+    require(TRANSACTION.amount() > Kryo::ZERO);
+
+    self.locked += TRANSACTION.amount();
+
+    event!(Locked, &TRANSACTION.from, &TRANSACTION.amount());
+  }
+
+  /// Sends everything locked to the beneficiary. Only callable by the beneficiary.
+  pub fn release(&mut self) -> Kryo {
+    require(TRANSACTION.from == self.beneficiary);
+    require(self.is_unlocked());
+    require(self.locked > Kryo::ZERO);
+
+    let amount = self.locked;
+
+    self.locked = Kryo::ZERO;
+    self.beneficiary.transfer(amount);
+
+    event!(Unlocked, &self.beneficiary, &amount);
+
+    amount
+  }
+}
@@ -1,6 +1,6 @@
-use super::{Address, NULL_ADDRESS};
+use core::{ops::Deref, ptr::addr_of};
 
-use lazy_static::lazy_static;
+use super::{Address, Kryo, NULL_ADDRESS};
 
 #[repr(C, packed)]
 #[derive(Copy, Clone, PartialEq)]
@@ -11,18 +11,31 @@ pub struct TransactionData {
 }
 
 #[no_mangle]
-static mut _TRANSACTION: TransactionData = TransactionData {
+pub(crate) static mut _TRANSACTION: TransactionData = TransactionData {
     from: NULL_ADDRESS,
     to: NULL_ADDRESS,
     value: 0
 };
 
-fn transaction() -> &'static TransactionData {
-  unsafe {
-    return &_TRANSACTION;
+impl TransactionData {
+  /// KRYO sent along with the call.
+  pub fn amount(&self) -> Kryo {
+    Kryo::from(self.value)
   }
 }
 
-lazy_static! {
-  pub static ref TRANSACTION: &'static TransactionData = transaction(); //*transaction();
+/// Reads `_TRANSACTION`, which the node writes before the call.
+pub struct TransactionContext;
+
+pub static TRANSACTION: TransactionContext = TransactionContext;
+
+impl Deref for TransactionContext {
+  type Target = TransactionData;
+
+  fn deref(&self) -> &TransactionData {
+    unsafe {
+      &*addr_of!(_TRANSACTION)
+    }
+  }
 }
+
@@ -1,25 +1,44 @@
-use std::ops;
+use core::ops;
+use alloc::{boxed::Box, string::String};
 use super::B32;
 use num_bigint::BigUint;
+use once_cell::race::OnceBox;
 use serde::{Serialize, Deserialize, de::Visitor};
-use lazy_static::lazy_static;
 
 fn u256_max_value() -> &'static BigUint {
-    return &U256_MAX_VALUE;
+    &U256_MAX_VALUE
 }
 
-lazy_static! {
-    pub static ref U256_MAX_VALUE: BigUint = BigUint::from_bytes_be(&[255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255]);
+pub struct U256MaxValue;
+
+pub static U256_MAX_VALUE: U256MaxValue = U256MaxValue;
+
+impl ops::Deref for U256MaxValue {
+    type Target = BigUint;
+
+    fn deref(&self) -> &BigUint {
+        static VALUE: OnceBox<BigUint> = OnceBox::new();
+        VALUE.get_or_init(|| Box::new(BigUint::from_bytes_be(&[255; 32])))
+    }
 }
 
 #[repr(C)]
-#[derive(Copy, Clone, Hash, Eq, PartialEq)]
+#[derive(Copy, Clone, Hash, Eq, PartialEq, PartialOrd, Ord)]
 pub struct U256(pub [u8; 32]);
 
+#[allow(clippy::len_without_is_empty)]
 impl U256 {
     pub fn as_string(&self) -> String {
         B32.encode(&self.0)
     }
+
+    pub const fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn as_ptr(&self) -> *const u8 {
+        self.0.as_ptr()
+    }
 }
 
 impl ops::Add<U256> for U256 {
@@ -86,12 +105,31 @@ impl ops::Rem<U256> for U256 {
     }
 }
 
+impl ops::BitXor<U256> for U256 {
+    type Output = U256;
+
+    fn bitxor(self, rhs: U256) -> Self::Output {
+        let mut bytes = self.0;
+
+        for (byte, other) in bytes.iter_mut().zip(rhs.0.iter()) {
+            *byte ^= other;
+        }
+
+        U256(bytes)
+    }
+}
+
 // TODO: implement rest
 
 impl Serialize for U256 {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: serde::Serializer {
+          // binary abi keeps the raw bytes
+          if !serializer.is_human_readable() {
+            return self.0.serialize(serializer);
+          }
+
           serializer.serialize_str(self.as_string().as_str())
     }
 }
@@ -100,6 +138,10 @@ impl <'de> Deserialize<'de> for U256 {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
         D: serde::Deserializer<'de> {
+        if !deserializer.is_human_readable() {
+          return Deserialize::deserialize(deserializer).map(U256);
+        }
+
         deserializer.deserialize_str(StringVisitor)
     }
 }
@@ -109,7 +151,7 @@ struct StringVisitor;
 impl<'de> Visitor<'de> for StringVisitor {
     type Value = U256;
 
-    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+    fn expecting(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
         write!(formatter, "a string represents U256")
     }
 
@@ -0,0 +1,18 @@
+use super::{TRANSACTION, require, is_owner, append_event, publish_event, event, __upgrade};
+
+/// Replaces the contract code with `new_code`. Only callable by the contract owner.
+///
+/// Once the current call finishes the node reads the old state through the `__state`
+/// export, instantiates the new code and hands that json to its `__migrate` export
+/// (generated from the `#[migrate]` method). The new `__state_version` must be higher
+/// than the one stored with the contract, otherwise the upgrade is rejected.
+pub fn upgrade(new_code: &[u8]) {
+  require(is_owner(&TRANSACTION.from));
+  require(!new_code.is_empty());
+
+  unsafe {
+    __upgrade(new_code.as_ptr(), new_code.len());
+  }
+
+  event!(Upgraded, &TRANSACTION.from);
+}
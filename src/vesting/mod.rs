@@ -0,0 +1,169 @@
+use serde::{Serialize, Deserialize};
+use super::{Address, Kryo, PointerTrait, BLOCK, CONTRACT, TRANSACTION, require, append_event, publish_event, event};
+
+/// What a vesting schedule pays out.
+#[derive(Serialize, Deserialize, Copy, Clone, PartialEq, Eq)]
+pub enum VestedAsset {
+  Kryo,
+  /// Tokens of the KRC20 contract at this address, moved with its `transfer_from` and `transfer`.
+  Krc20(Address)
+}
+
+/// KRYO or a KRC20 token released to a beneficiary over time.
+///
+/// Nothing vests before `start + cliff`, after that the vested part grows linearly until
+/// everything has vested at `start + duration`. With `cliff == duration` the whole amount is
+/// released at once. Times are unix milliseconds, compared against `BLOCK.timestamp`.
+///
+/// Schedules pay out KRYO and count in `Kryo`, `with_token` turns one into a
+/// `VestingSchedule<u64>` paying out a KRC20 token, counted in the token's smallest unit.
+#[derive(Serialize, Deserialize, Copy, Clone, PartialEq)]
+pub struct VestingSchedule<A = Kryo> {
+  beneficiary: Address,
+  asset: VestedAsset,
+  start: u64,
+  cliff: u64,
+  duration: u64,
+  total: A,
+  released: A
+}
+
+impl VestingSchedule {
+  /// Linear release between `start` and `start + duration`.
+  pub fn linear(beneficiary: Address, start: u64, duration: u64) -> VestingSchedule {
+    VestingSchedule::with_cliff(beneficiary, start, 0, duration)
+  }
+
+  /// Everything is released at `unlock_at`.
+  pub fn cliff(beneficiary: Address, unlock_at: u64) -> VestingSchedule {
+    VestingSchedule::with_cliff(beneficiary, unlock_at, 0, 0)
+  }
+
+  /// Linear release between `start` and `start + duration`, but nothing before `start + cliff`.
+  pub fn with_cliff(beneficiary: Address, start: u64, cliff: u64, duration: u64) -> VestingSchedule {
+    require(cliff <= duration);
+    require(start.checked_add(duration).is_some());
+
+    VestingSchedule {
+      beneficiary,
+      asset: VestedAsset::Kryo,
+      start,
+      cliff,
+      duration,
+      total: Kryo::ZERO,
+      released: Kryo::ZERO
+    }
+  }
+
+  /// Pays the schedule out in tokens of the KRC20 contract at `token` instead of KRYO.
+  pub fn with_token(self, token: Address) -> VestingSchedule<u64> {
+    require(self.total == Kryo::ZERO);
+
+    VestingSchedule {
+      beneficiary: self.beneficiary,
+      asset: VestedAsset::Krc20(token),
+      start: self.start,
+      cliff: self.cliff,
+      duration: self.duration,
+      total: 0,
+      released: 0
+    }
+  }
+
+  /// Adds the KRYO sent with the call to the schedule.
+  pub fn fund(&mut self) {
+    require(TRANSACTION.amount() > Kryo::ZERO);
+
+    self.add(TRANSACTION.amount());
+  }
+}
+
+impl VestingSchedule<u64> {
+  /// Moves `amount` tokens from the caller to the contract and adds them to the schedule.
+  /// The caller has to approve the contract for at least `amount` on the token first.
+  pub fn fund_tokens(&mut self, amount: u64) {
+    let token = match self.asset {
+      VestedAsset::Krc20(token) => Some(token),
+      VestedAsset::Kryo => None
+    };
+
+    require(token.is_some());
+    require(amount > 0);
+
+    let params = serde_json::to_string(&(TRANSACTION.from, CONTRACT.address, amount)).unwrap();
+    require(token.unwrap().call("transfer_from", &params, 0u64));
+
+    self.add(amount);
+  }
+}
+
+impl<A: Copy + From<u64> + Into<u64> + PointerTrait> VestingSchedule<A> {
+  pub fn beneficiary(&self) -> Address {
+    self.beneficiary
+  }
+
+  pub fn asset(&self) -> VestedAsset {
+    self.asset
+  }
+
+  /// Everything ever put into the schedule, including what has already been released.
+  pub fn total(&self) -> A {
+    self.total
+  }
+
+  pub fn released(&self) -> A {
+    self.released
+  }
+
+  fn add(&mut self, amount: A) {
+    let total = self.total.into().checked_add(amount.into());
+    require(total.is_some());
+
+    self.total = A::from(total.unwrap());
+
+    event!(VestingFunded, &TRANSACTION.from, &amount);
+  }
+
+  /// Amount vested at `timestamp`, released or not.
+  pub fn vested_at(&self, timestamp: u64) -> A {
+    if timestamp < self.start + self.cliff {
+      return A::from(0);
+    }
+
+    if timestamp >= self.start + self.duration {
+      return self.total;
+    }
+
+    let elapsed = (timestamp - self.start) as u128;
+    let vested = self.total.into() as u128 * elapsed / self.duration as u128;
+
+    A::from(vested as u64)
+  }
+
+  /// Amount the beneficiary could collect right now.
+  pub fn releasable(&self) -> A {
+    A::from(self.vested_at(BLOCK.timestamp).into() - self.released.into())
+  }
+
+  /// Sends the vested but not yet released amount to the beneficiary. Only callable by the beneficiary.
+  pub fn release(&mut self) -> A {
+    require(TRANSACTION.from == self.beneficiary);
+
+    let amount = self.releasable();
+    require(amount.into() > 0);
+
+    self.released = A::from(self.released.into() + amount.into());
+
+    match self.asset {
+      VestedAsset::Kryo => self.beneficiary.transfer(amount),
+      VestedAsset::Krc20(token) => {
+        let params = serde_json::to_string(&(self.beneficiary, amount.into())).unwrap();
+        require(token.call("transfer", &params, 0u64));
+      }
+    }
+
+    event!(VestingReleased, &self.beneficiary, &amount);
+
+    amount
+  }
+}
@@ -0,0 +1,114 @@
+use kryolite_smart_contract::*;
+use kryolite_smart_contract::de::DeserializeOwned;
+use kryolite_abi::Type;
+
+fn roundtrip<T: Serialize + DeserializeOwned + PartialEq>(value: T) {
+  let bytes = abi_encode(&value).unwrap();
+  assert!(abi_decode::<T>(&bytes).unwrap() == value);
+}
+
+// what kryolite-abi writes for the JSON form of `value`, has to be the bytes the contract reads
+fn same_as_kryolite_abi<T: Serialize + ?Sized>(type_name: &str, value: &T) {
+  let ty = Type::parse(type_name).unwrap();
+  let json = serde_json::to_value(value).unwrap();
+
+  let mut expected = Vec::new();
+  kryolite_abi::encode(&ty, &json, "value", &mut expected).unwrap();
+
+  assert_eq!(abi_encode(value).unwrap(), expected, "{}", type_name);
+}
+
+#[derive(Serialize, Deserialize, PartialEq)]
+struct Transfer {
+  to: Address,
+  amount: Kryo,
+  memo: Option<String>
+}
+
+#[derive(Serialize, Deserialize, PartialEq)]
+enum Action {
+  Pause,
+  Transfer(Transfer),
+  Set { key: String, value: u64 }
+}
+
+#[test]
+fn roundtrips() {
+  roundtrip(0u8);
+  roundtrip(u16::MAX);
+  roundtrip(-5i32);
+  roundtrip(u64::MAX);
+  roundtrip(i64::MIN);
+  roundtrip(u128::MAX);
+  roundtrip(i128::MIN);
+  roundtrip(true);
+  roundtrip('é');
+  roundtrip(String::from("kryolite"));
+
+  roundtrip(Some(7u32));
+  roundtrip(None::<u32>);
+  roundtrip(vec![1u16, 2, 3]);
+  roundtrip(Vec::<String>::new());
+  roundtrip(OrderedMap::from([(3u32, String::from("c")), (1, String::from("a"))]));
+  roundtrip((1u8, String::from("x"), false));
+
+  roundtrip(Address([7; 26]));
+  roundtrip(U256([9; 32]));
+  roundtrip(Kryo::from_units(1_500_000));
+
+  roundtrip(Transfer { to: Address([1; 26]), amount: Kryo::from_units(42), memo: Some(String::from("rent")) });
+  roundtrip(vec![Action::Pause, Action::Set { key: String::from("fee"), value: 3 }]);
+}
+
+#[test]
+fn layout() {
+  assert_eq!(abi_encode(&0x0102u16).unwrap(), [2, 1]);
+  assert_eq!(abi_encode(&Some(1u8)).unwrap(), [1, 1]);
+  assert_eq!(abi_encode(&None::<u8>).unwrap(), [0]);
+  assert_eq!(abi_encode("ab").unwrap(), [2, 0, 0, 0, b'a', b'b']);
+  assert_eq!(abi_encode(&Address([7; 26])).unwrap(), [7; 26]);
+  assert_eq!(abi_encode(&Kryo::from_units(5)).unwrap(), 5u64.to_le_bytes());
+  assert_eq!(abi_encode(&Action::Set { key: String::new(), value: 1 }).unwrap(), [2, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0]);
+}
+
+#[test]
+fn rejects_malformed_input() {
+  assert_eq!(abi_decode::<u32>(&[1, 2]), Err(AbiError::Eof));
+  assert_eq!(abi_decode::<String>(&[5, 0, 0, 0, b'a']), Err(AbiError::Eof));
+  assert_eq!(abi_decode::<Vec<u8>>(&[]), Err(AbiError::Eof));
+  assert_eq!(abi_decode::<bool>(&[2]), Err(AbiError::InvalidBool(2)));
+  assert_eq!(abi_decode::<Option<u8>>(&[2, 1]), Err(AbiError::InvalidOption(2)));
+  assert_eq!(abi_decode::<char>(&0xD800u32.to_le_bytes()), Err(AbiError::InvalidChar(0xD800)));
+  assert_eq!(abi_decode::<String>(&[2, 0, 0, 0, 0xC3, 0x28]), Err(AbiError::InvalidUtf8));
+  assert_eq!(abi_decode::<u8>(&[1, 2]), Err(AbiError::TrailingBytes));
+  assert_eq!(abi_decode::<()>(&[0]), Err(AbiError::TrailingBytes));
+}
+
+#[test]
+fn matches_kryolite_abi() {
+  same_as_kryolite_abi("u8", &200u8);
+  same_as_kryolite_abi("i16", &-300i16);
+  same_as_kryolite_abi("u64", &u64::MAX);
+  same_as_kryolite_abi("i64", &i64::MIN);
+  same_as_kryolite_abi("u128", &u128::MAX);
+  same_as_kryolite_abi("i128", &i128::MIN);
+  same_as_kryolite_abi("bool", &true);
+  same_as_kryolite_abi("char", &'é');
+  same_as_kryolite_abi("String", "kryolite");
+
+  same_as_kryolite_abi("Option<u32>", &Some(7u32));
+  same_as_kryolite_abi("Option<u32>", &None::<u32>);
+  same_as_kryolite_abi("Vec<u16>", &vec![1u16, 2, 3]);
+  same_as_kryolite_abi("[u8; 3]", &[1u8, 2, 3]);
+  same_as_kryolite_abi("(u8, String, bool)", &(1u8, "x", false));
+
+  // the JSON object has the keys in text order, "-1" < "10" < "9"
+  same_as_kryolite_abi("OrderedMap<i32, bool>", &OrderedMap::from([(9, true), (10, false), (-1, true)]));
+  same_as_kryolite_abi("OrderedMap<String, Vec<u8>>", &OrderedMap::from([("b", vec![1u8]), ("a", vec![])]));
+  same_as_kryolite_abi("OrderedMap<Address, u64>", &OrderedMap::from([(Address([200; 26]), 1u64), (Address([3; 26]), 2)]));
+
+  same_as_kryolite_abi("Address", &Address([7; 26]));
+  same_as_kryolite_abi("U256", &U256([9; 32]));
+  same_as_kryolite_abi("Kryo", &Kryo::from_units(1_500_000));
+  same_as_kryolite_abi("(Address, Kryo, Option<String>)", &(Address([1; 26]), Kryo::from_units(42), Some("rent")));
+}
@@ -0,0 +1,103 @@
+#![cfg(feature = "testing")]
+
+use kryolite_smart_contract::*;
+
+#[smart_contract]
+#[derive(Serialize, Clone, Default)]
+pub struct Meter {
+  pub checks: u64
+}
+
+#[smart_contract]
+impl Meter {
+  pub fn new() -> Meter {
+    Meter { checks: 0 }
+  }
+
+  pub fn ping(&mut self) {}
+
+  pub fn check(&mut self, times: u64) {
+    for _ in 0..times {
+      remaining_gas();
+    }
+
+    self.checks += times;
+  }
+
+  pub fn fail(&mut self) {
+    remaining_gas();
+    require(false);
+  }
+}
+
+fn env(call: u64) -> TestEnv {
+  let mut env = TestEnv::new();
+  env.set_gas_table(GasTable { call, remaining_gas: 1_000, ..GasTable::ESTIMATE });
+  env
+}
+
+#[test]
+fn records_every_call() {
+  let mut env = env(41_203);
+  let mut meter = env.deploy(Meter::new);
+
+  env.call("ping", &mut meter, |meter| meter.ping()).unwrap();
+  assert_eq!(env.last_gas(), 41_203);
+
+  env.call("check", &mut meter, |meter| meter.check(0)).unwrap();
+  env.call("check", &mut meter, |meter| meter.check(3)).unwrap();
+  env.call("check", &mut meter, |meter| meter.check(2)).unwrap();
+  assert_eq!(env.last_gas(), 43_203);
+
+  let check = env.gas_report().method("check").unwrap();
+  assert_eq!((check.calls, check.reverted, check.min, check.max, check.total), (3, 0, 41_203, 44_203, 128_609));
+  assert_eq!(check.average(), 42_869);
+
+  assert_eq!(env.gas_report().methods().map(|(name, _)| name).collect::<Vec<&str>>(), ["check", "new", "ping"]);
+  assert!(env.gas_report().method("fail").is_none());
+}
+
+#[test]
+fn counts_reverted_calls() {
+  let mut env = env(41_203);
+  let mut meter = env.deploy(Meter::new);
+
+  assert!(env.call("fail", &mut meter, |meter| meter.fail()).is_err());
+  assert!(env.call("fail", &mut meter, |meter| meter.fail()).is_err());
+
+  // gas of reverted calls is still charged
+  let fail = env.gas_report().method("fail").unwrap();
+  assert_eq!((fail.calls, fail.reverted, fail.min, fail.max), (2, 2, 42_203, 42_203));
+}
+
+#[test]
+fn prints_a_line_per_method() {
+  let mut env = env(41_203);
+  let mut meter = env.deploy(Meter::new);
+
+  // deploying calls the constructor
+  assert_eq!(env.gas_report().to_string(), "new: 41_203 host gas\n");
+
+  env.call("ping", &mut meter, |meter| meter.ping()).unwrap();
+  env.call("check", &mut meter, |meter| meter.check(0)).unwrap();
+  env.call("check", &mut meter, |meter| meter.check(1_000)).unwrap();
+  assert!(env.call("fail", &mut meter, |meter| meter.fail()).is_err());
+
+  assert_eq!(env.gas_report().to_string(), concat!(
+    "check: 541_203 host gas (41_203 - 1_041_203), 2 calls\n",
+    "fail: 42_203 host gas, 1 reverted\n",
+    "new: 41_203 host gas\n",
+    "ping: 41_203 host gas\n"
+  ));
+}
+
+#[test]
+fn separates_thousands() {
+  for (call, text) in [(0, "0"), (999, "999"), (1_000, "1_000"), (123_456_789, "123_456_789")] {
+    let mut env = env(call);
+    let mut meter = env.deploy(Meter::new);
+
+    env.call("ping", &mut meter, |meter| meter.ping()).unwrap();
+    assert_eq!(env.gas_report().to_string(), format!("new: {0} host gas\nping: {0} host gas\n", text));
+  }
+}
@@ -0,0 +1,104 @@
+use kryolite_smart_contract::*;
+
+fn leaves(count: u8) -> Vec<U256> {
+  (0..count).map(|i| hash_leaf(&[i])).collect()
+}
+
+fn h256(hex: &str) -> U256 {
+  let bytes: Vec<u8> = (0..64).step_by(2).map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap()).collect();
+  U256(bytes.try_into().unwrap())
+}
+
+// abi.encode(address, uint256)
+fn abi_encode(address: [u8; 20], amount: u128) -> Vec<u8> {
+  let mut out = vec![0; 12];
+  out.extend_from_slice(&address);
+  out.extend_from_slice(&[0; 16]);
+  out.extend_from_slice(&amount.to_be_bytes());
+  out
+}
+
+#[test]
+fn proofs_verify_for_every_leaf() {
+  for count in 1..=9 {
+    let leaves = leaves(count);
+    let tree = MerkleTree::new(&leaves);
+
+    for (i, leaf) in leaves.iter().enumerate() {
+      let proof = tree.proof(i);
+      assert!(verify_proof(tree.root(), *leaf, &proof), "{} of {}", i, count);
+    }
+  }
+}
+
+#[test]
+fn proofs_reject_other_leaves_and_roots() {
+  let leaves = leaves(5);
+  let tree = MerkleTree::new(&leaves);
+  let proof = tree.proof(2);
+
+  assert!(!verify_proof(tree.root(), leaves[3], &proof));
+  assert!(!verify_proof(tree.root(), hash_leaf(b"not in the tree"), &proof));
+  assert!(!verify_proof(MerkleTree::new(&leaves[..4]).root(), leaves[2], &proof));
+  assert!(!verify_proof(tree.root(), leaves[2], &proof[1..]));
+}
+
+#[test]
+fn indexed_proofs_need_the_right_index() {
+  for count in 1..=9 {
+    let leaves = leaves(count);
+    let tree = MerkleTree::new_indexed(&leaves);
+
+    for (i, leaf) in leaves.iter().enumerate() {
+      let proof = tree.proof(i);
+
+      assert!(verify_indexed_proof(tree.root(), *leaf, i as u64, &proof), "{} of {}", i, count);
+      assert!(!verify_indexed_proof(tree.root(), *leaf, i as u64 + 1, &proof), "{} of {}", i, count);
+    }
+  }
+
+  // the index has to fit in the depth of the proof
+  let leaves = leaves(4);
+  let tree = MerkleTree::new_indexed(&leaves);
+  assert!(!verify_indexed_proof(tree.root(), leaves[1], 5, &tree.proof(1)));
+}
+
+#[test]
+fn multiproofs() {
+  let leaves = leaves(7);
+  let tree = MerkleTree::new(&leaves);
+
+  for indices in [vec![0], vec![1, 2], vec![0, 3, 6], vec![6, 5, 4, 3, 2, 1, 0], vec![4, 4]] {
+    let multi = tree.multiproof(&indices);
+
+    assert!(verify_multiproof(tree.root(), &multi.leaves, &multi.proof, &multi.proof_flags), "{:?}", indices);
+    assert!(indices.iter().all(|i| multi.leaves.contains(&leaves[*i])));
+
+    let mut wrong = multi.leaves.clone();
+    wrong[0] = hash_leaf(b"not in the tree");
+    assert!(!verify_multiproof(tree.root(), &wrong, &multi.proof, &multi.proof_flags), "{:?}", indices);
+  }
+
+  let multi = tree.multiproof(&[1, 2]);
+  let mut flags = multi.proof_flags.clone();
+  flags.pop();
+  assert!(!verify_multiproof(tree.root(), &multi.leaves, &multi.proof, &flags));
+}
+
+// example of the @openzeppelin/merkle-tree README, `StandardMerkleTree.of(values, ["address", "uint256"])`,
+// which sorts the leaf hashes before building the tree
+#[test]
+fn openzeppelin_standard_merkle_tree() {
+  let mut leaves = vec![
+    hash_leaf(&abi_encode([0x11; 20], 5_000_000_000_000_000_000)),
+    hash_leaf(&abi_encode([0x22; 20], 2_500_000_000_000_000_000))
+  ];
+  leaves.sort();
+
+  let tree = MerkleTree::new(&leaves);
+  let root = h256("d4dee0beab2d53f2cc83e567171bd2820e49898130a22622b10ead383e90bd77");
+  let proof = [h256("b92c48e9d7abe27fd8dfd6b5dfdbfb1c9a463f80c712b66f3a5180a090cccafc")];
+
+  assert!(tree.root() == root);
+  assert!(verify_proof(root, hash_leaf(&abi_encode([0x11; 20], 5_000_000_000_000_000_000)), &proof));
+}
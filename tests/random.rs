@@ -0,0 +1,56 @@
+#![cfg(feature = "testing")]
+
+use kryolite_smart_contract::*;
+
+#[smart_contract]
+#[derive(Serialize, Clone, Default)]
+pub struct Draws {
+  pub values: Vec<u64>
+}
+
+#[smart_contract]
+impl Draws {
+  pub fn new() -> Draws {
+    Draws { values: Vec::new() }
+  }
+
+  pub fn draw(&mut self) {
+    self.values.push(random_u64());
+  }
+
+  pub fn draw_twice(&mut self) {
+    self.draw();
+    self.draw();
+  }
+}
+
+// the methods are called directly, so only the generated entry code resets the stream
+#[test]
+fn every_call_draws_from_its_own_seed() {
+  let mut env = TestEnv::new();
+  let mut draws = env.deploy(Draws::new);
+
+  env.set_random_seed(U256([1; 32]));
+  draws.draw();
+
+  env.set_random_seed(U256([2; 32]));
+  draws.draw();
+
+  env.set_random_seed(U256([1; 32]));
+  draws.draw();
+
+  assert_ne!(draws.values[0], draws.values[1]);
+  assert_eq!(draws.values[0], draws.values[2]);
+}
+
+#[test]
+fn nested_calls_continue_the_stream() {
+  let mut env = TestEnv::new();
+  let mut draws = env.deploy(Draws::new);
+
+  env.set_random_seed(U256([1; 32]));
+  draws.draw_twice();
+
+  assert_ne!(draws.values[0], draws.values[1]);
+  assert_eq!(draws.values[0], Rng::from_seed(U256([1; 32])).next_u64());
+}
@@ -0,0 +1,36 @@
+use kryolite_smart_contract::*;
+
+fn hex<const N: usize>(text: &str) -> [u8; N] {
+  let bytes: Vec<u8> = (0..text.len()).step_by(2).map(|i| u8::from_str_radix(&text[i..i + 2], 16).unwrap()).collect();
+  bytes.try_into().unwrap()
+}
+
+// RFC 8032, section 7.1, test 1
+const PUBLIC_KEY: &str = "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a";
+const SIGNATURE: &str = "e5564300c360ac729086e2cc806e828a84877f1eb8e5d974d873e065224901555fb8821590a33bacc61e39701cf9b46bd25bf5f0595bbe24655141438e7a100b";
+
+#[test]
+fn verifies_ed25519_signatures() {
+  let key = PublicKey(hex(PUBLIC_KEY));
+  let signature = Signature(hex(SIGNATURE));
+
+  assert!(verify_signature(&key, b"", &signature));
+  assert!(!verify_signature(&key, b"x", &signature));
+
+  let mut tampered = signature;
+  tampered.0[0] ^= 1;
+  assert!(!verify_signature(&key, b"", &tampered));
+
+  // not a point on the curve
+  assert!(!verify_signature(&PublicKey([0xff; 32]), b"", &signature));
+}
+
+// only the layout, the mock derivation isn't checked against the node
+#[test]
+fn mock_addresses_are_wallet_addresses() {
+  let address = PublicKey(hex(PUBLIC_KEY)).to_address();
+
+  assert_eq!(address.0[..2], [0, 0]);
+  assert!(address == PublicKey(hex(PUBLIC_KEY)).to_address());
+  assert!(address != PublicKey([1; 32]).to_address());
+}
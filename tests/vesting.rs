@@ -0,0 +1,89 @@
+#![cfg(feature = "testing")]
+
+use kryolite_smart_contract::*;
+
+const TOKEN: Address = Address([0x70; 26]);
+
+#[smart_contract]
+#[derive(Serialize, Clone)]
+pub struct Grant {
+  pub schedule: VestingSchedule<u64>,
+  pub kryo: VestingSchedule
+}
+
+#[smart_contract]
+impl Grant {
+  #[allow(clippy::new_without_default)]
+  pub fn new() -> Grant {
+    Grant {
+      schedule: VestingSchedule::linear(test_account(1), 1000, 1000).with_token(TOKEN),
+      kryo: VestingSchedule::cliff(test_account(2), 2000)
+    }
+  }
+
+  pub fn fund(&mut self, amount: u64) {
+    self.schedule.fund_tokens(amount);
+  }
+
+  pub fn release(&mut self) -> u64 {
+    self.schedule.release()
+  }
+
+  pub fn fund_kryo(&mut self) {
+    self.kryo.fund();
+  }
+
+  pub fn release_kryo(&mut self) -> Kryo {
+    self.kryo.release()
+  }
+}
+
+#[test]
+fn releases_tokens_through_the_token_contract() {
+  let mut env = TestEnv::new();
+  let mut grant = env.deploy(Grant::new);
+
+  env.call("fund", &mut grant, |grant| grant.fund(500)).unwrap();
+  assert_eq!(grant.schedule.total(), 500);
+
+  env.set_block(2, 1500);
+  env.set_caller(test_account(1));
+  assert_eq!(env.call("release", &mut grant, |grant| grant.release()), Ok(250));
+
+  let calls = env.contract_calls();
+  assert_eq!(calls.len(), 2);
+
+  assert!(calls.iter().all(|call| call.contract == TOKEN && call.value == Kryo::ZERO));
+  assert_eq!(calls[0].method, "transfer_from");
+  assert_eq!(calls[0].params, format!("[\"{}\",\"{}\",500]", test_account(0).as_string(), env.contract_address().as_string()));
+  assert_eq!(calls[1].method, "transfer");
+  assert_eq!(calls[1].params, format!("[\"{}\",250]", test_account(1).as_string()));
+}
+
+#[test]
+fn reverts_when_the_token_refuses() {
+  let mut env = TestEnv::new();
+  let mut grant = env.deploy(Grant::new);
+
+  env.set_call_result(false);
+  assert!(env.call("fund", &mut grant, |grant| grant.fund(500)).is_err());
+  assert_eq!(grant.schedule.total(), 0);
+}
+
+#[test]
+fn kryo_schedules_count_in_kryo() {
+  let mut env = TestEnv::new();
+  let mut grant = env.deploy(Grant::new);
+
+  env.set_balance(test_account(0), Kryo::from_units(100));
+  env.set_value(Kryo::from_units(100));
+  env.call("fund_kryo", &mut grant, |grant| grant.fund_kryo()).unwrap();
+  assert_eq!(grant.kryo.total(), Kryo::from_units(100));
+
+  env.set_value(Kryo::ZERO);
+  env.set_block(2, 2000);
+  env.set_caller(test_account(2));
+  assert_eq!(env.call("release_kryo", &mut grant, |grant| grant.release_kryo()), Ok(Kryo::from_units(100)));
+  assert_eq!(env.balance_of(&test_account(2)), Kryo::from_units(100));
+  assert_eq!(grant.kryo.released(), Kryo::from_units(100));
+}